@@ -14,6 +14,28 @@ mod errors {
         description("encountered errors while tangling output")
         display("encountered errors while tangling output")
       }
+
+      UnknownErrorKind(name: String) {
+        description("unknown kind of error")
+        display("unknown kind of error '{}', expected one of: {}",
+                name,
+                ::kaiseki::processing_errors::KIND_NAMES.join(", "))
+      }
+
+      CouldNotWriteReport(filename: String) {
+        description("could not write report")
+        display("could not write report to '{}'", filename)
+      }
+
+      CouldNotReadTarget(filename: String) {
+        description("could not read file to compare output against")
+        display("could not read '{}' to compare output against", filename)
+      }
+
+      Unchanged(filename: String) {
+        description("output is unchanged")
+        display("output is identical to '{}'", filename)
+      }
     }
     links {
       Input(::kaiseki::input::errors::Error, ::kaiseki::input::errors::ErrorKind);
@@ -24,12 +46,16 @@ mod errors {
 use structopt::StructOpt;
 
 use std::process;
-use std::io::stderr;
+use std::io::{stderr, stdout};
 use std::io::Write;
 
 use errors::*;
 use kaiseki::input;
 
+/// The exit status when `--fail-if-unchanged` finds the output unchanged,
+/// to tell it apart from failing because of errors.
+const UNCHANGED_STATUS: i32 = 2;
+
 #[derive(StructOpt, Debug)]
 #[structopt(name = "kaiseki", about = "literate programming preprocessor")]
 struct CLIArgs {
@@ -40,45 +66,221 @@ struct CLIArgs {
   comment_leader: Option<String>,
 
   #[structopt(short = "i", long = "ignore-errors", help = "Exit normally, ignore errors")]
-  ignore_errors: bool
+  ignore_errors: bool,
+
+  #[structopt(long = "allow", help = "Don't report errors of the given kind")]
+  allow: Vec<String>,
+
+  #[structopt(long = "tab-size", help = "Number of columns a tab counts for", default_value = "8")]
+  tab_size: usize,
+
+  #[structopt(long = "report-json", help = "Write a JSON summary of the run to the given file")]
+  report_json: Option<String>,
+
+  #[structopt(long = "files-from", help = "Also tangle the files listed in the given file, or stdin if '-'")]
+  files_from: Option<String>,
+
+  #[structopt(long = "only", help = "Only output the contents of the given label")]
+  only: Option<String>,
+
+  #[structopt(long = "max-line-length", help = "Warn about output lines longer than the given number of columns")]
+  max_line_length: Option<usize>,
+
+  #[structopt(long = "dump-structure", help = "Print where each block of lines was placed to stderr")]
+  dump_structure: bool,
+
+  #[structopt(long = "sort-inputs", help = "Process files in order of their names")]
+  sort_inputs: bool,
+
+  #[structopt(long = "normalize-trailing", help = "Remove blank lines from the end of the output")]
+  normalize_trailing: bool,
+
+  #[structopt(long = "normalize-newlines-in-blocks", help = "Split block lines containing newlines into separate output lines")]
+  normalize_newlines_in_blocks: bool,
+
+  #[structopt(long = "before-in-order", help = "Place blocks inserted before the same label in the order they were seen")]
+  before_in_order: bool,
+
+  #[structopt(long = "fail-if-unchanged", help = "Exit with status 2 if the output is identical to the given file")]
+  fail_if_unchanged: Option<String>,
+
+  #[structopt(long = "log-prefix", help = "Start each error and warning with the given text", default_value = "kaiseki")]
+  log_prefix: String
 }
 
 fn main() {
+  use kaiseki::report::report_errors;
+
   let cli_args = CLIArgs::from_args();
+  let log_prefix = cli_args.log_prefix.clone();
 
-  if let Err(ref e) = go(cli_args) {
-    writeln!(stderr(), "kaiseki: {}", e)
+  let result = go(cli_args);
+
+  if let Err(ref e) = result {
+    report_errors(&[e], &log_prefix, stderr())
       .unwrap();
 
     for e in e.iter().skip(1) {
       writeln!(stderr(), "  caused by: {}", e)
         .unwrap();
     }
+  }
+
+  process::exit(exit_status(&result));
+}
 
-    process::exit(1);
+/// The status to exit with after a run with the given result.
+fn exit_status(result: &Result<()>) -> i32 {
+  match *result {
+    Ok(()) => 0,
+    Err(ref e) => match *e.kind() {
+      ErrorKind::Unchanged(_) => UNCHANGED_STATUS,
+      _ => 1
+    }
   }
 }
 
 fn go(args: CLIArgs) -> Result<()> {
-  let files = input::open_files(args.files)?;
+  use kaiseki::processing_errors::{KIND_NAMES, filter_allowed};
+
+  for kind in &args.allow {
+    if !KIND_NAMES.contains(&kind.as_str()) {
+      bail!(ErrorKind::UnknownErrorKind(kind.clone()));
+    }
+  }
+
+  let files = match args.files_from {
+    Some(source) => input::files_from(source, args.files)?,
+    None => args.files
+  };
+  let files = input::open_files(files)?;
 
   let output_options = kaiseki::OutputOptions {
-    comment: args.comment_leader
+    comment: args.comment_leader,
+    tab_size: args.tab_size,
+    max_line_length: args.max_line_length,
+    sort_inputs: args.sort_inputs,
+    normalize_trailing: args.normalize_trailing,
+    split_embedded_newlines: args.normalize_newlines_in_blocks,
+    before_in_order: args.before_in_order,
+    ..Default::default()
+  };
+
+  let tangler = kaiseki::Tangler::new(files, &output_options);
+
+  if args.dump_structure {
+    for line in tangler.dump_structure() {
+      eprintln!("{}", line);
+    }
+  }
+
+  let (output, errors, stats) = match args.fail_if_unchanged {
+    // Comparing against the target needs all of the output at once.
+    Some(_) => {
+      let (output, errors, stats) = match args.only {
+        Some(ref label) => tangler.tangle_label(output_options, label),
+        None => tangler.tangle_output(output_options)
+      };
+
+      for line in &output {
+        println!("{}", line);
+      }
+
+      (output, errors, stats)
+    },
+    None => {
+      let stdout = stdout();
+      let (_, errors, stats) = match args.only {
+        Some(ref label) => tangler.write_label(output_options, label, stdout.lock()),
+        None => tangler.write_output(output_options, stdout.lock())
+      };
+
+      (Vec::new(), errors, stats)
+    }
   };
+  let errors = filter_allowed(errors, &args.allow);
 
-  let (output, errors) = kaiseki::tangle_output(files, output_options);
-  
-  for line in output {
-    println!("{}", line);
+  if let Some(report_file) = args.report_json {
+    write_report(report_file, &stats, &errors)?;
   }
 
-  if !args.ignore_errors && !errors.is_empty() {
-    for error in errors {
-      writeln!(stderr(), "kaiseki: {}", error)
-        .unwrap();
+  check_outcome(&output, &errors, args.ignore_errors, &args.log_prefix, args.fail_if_unchanged)
+}
+
+/// Fail if any errors were found, unless they're being ignored, and then
+/// if the output is identical to `fail_if_unchanged`, if that was given.
+fn check_outcome(output: &[String],
+                 errors: &[kaiseki::processing_errors::Error],
+                 ignore_errors: bool,
+                 log_prefix: &str,
+                 fail_if_unchanged: Option<String>) -> Result<()> {
+  use kaiseki::report::report_errors;
+
+  if !ignore_errors && !errors.is_empty() {
+    report_errors(errors, log_prefix, stderr())
+      .unwrap();
+    return Err(ErrorKind::Processing.into());
+  }
+
+  if let Some(target) = fail_if_unchanged {
+    let unchanged = kaiseki::output_matches_file(output, &target)
+      .chain_err(|| ErrorKind::CouldNotReadTarget(target.clone()))?;
+    if unchanged {
+      bail!(ErrorKind::Unchanged(target));
     }
-    Err(ErrorKind::Processing.into())
-  } else {
-    Ok(())
+  }
+
+  Ok(())
+}
+
+fn write_report(filename: String, stats: &kaiseki::Stats, errors: &[kaiseki::processing_errors::Error]) -> Result<()> {
+  use std::fs;
+  use kaiseki::report::json_report;
+
+  let written = fs::File::create(&filename)
+    .and_then(|mut file| writeln!(file, "{}", json_report(stats, errors)));
+
+  written.chain_err(|| ErrorKind::CouldNotWriteReport(filename))
+}
+
+#[cfg(test)]
+mod status_tests {
+  use super::{check_outcome, exit_status};
+
+  use kaiseki::processing_errors::ErrorKind;
+
+  fn output() -> Vec<String> {
+    include_str!("../../tests/tangling/test22/output").lines()
+      .map(|line| line.to_string())
+      .collect()
+  }
+
+  fn status(errors: &[::kaiseki::processing_errors::Error], ignore_errors: bool, target: &str) -> i32 {
+    exit_status(&check_outcome(&output(), errors, ignore_errors, "kaiseki", Some(target.to_string())))
+  }
+
+  #[test]
+  fn test_unchanged_status() {
+    assert_eq!(status(&[], false, "tests/tangling/test22/output"), 2);
+    assert_eq!(status(&[], false, "tests/tangling/test21/output"), 0);
+    assert_eq!(status(&[], false, "tests/tangling/test22/nonexistent"), 0);
+    assert_eq!(status(&[], false, "tests/tangling"), 1);
+  }
+
+  #[test]
+  fn test_unchanged_status_with_errors() {
+    let errors = vec![ErrorKind::MissingLabel("Missing".to_string()).into()];
+
+    // Errors take precedence, unless they're ignored.
+    assert_eq!(status(&errors, false, "tests/tangling/test22/output"), 1);
+    assert_eq!(status(&errors, true, "tests/tangling/test22/output"), 2);
+    assert_eq!(status(&errors, true, "tests/tangling/test21/output"), 0);
+  }
+
+  #[test]
+  fn test_exit_status() {
+    assert_eq!(exit_status(&Ok(())), 0);
+    assert_eq!(exit_status(&Err(super::ErrorKind::Processing.into())), 1);
+    assert_eq!(exit_status(&Err(super::ErrorKind::Unchanged("output".to_string()).into())), 2);
   }
 }
@@ -9,6 +9,16 @@ pub mod errors {
         description("could not open file")
         display("could not open file '{}'", filename)
       }
+
+      CouldNotReadFileList(filename: String) {
+        description("could not read list of files")
+        display("could not read list of files from '{}'", filename)
+      }
+
+      AmbiguousStdin {
+        description("stdin used for both the list of files and an input file")
+        display("can't read both the list of files and an input file from stdin")
+      }
     }
   }
 }
@@ -39,6 +49,40 @@ pub fn open_files(mut files: Vec<String>) -> Result<Vec<File>> {
   Ok(output)
 }
 
+/// Read a newline-separated list of files from `source`, which might be
+/// '-' for `stdin()`, and add them after the files already given.
+pub fn files_from(source: String, mut files: Vec<String>) -> Result<Vec<String>> {
+  use std::io;
+  use std::fs;
+
+  if &source == "-" && files.iter().any(|file| file == "-") {
+    bail!(ErrorKind::AmbiguousStdin);
+  }
+
+  let listed = if &source == "-" {
+    read_file_list(io::stdin())
+  } else {
+    fs::File::open(&source).and_then(read_file_list)
+  };
+
+  files.extend(listed.chain_err(|| ErrorKind::CouldNotReadFileList(source))?);
+
+  Ok(files)
+}
+
+/// Read filenames, one per line, skipping blank lines.
+pub fn read_file_list<R: Read>(mut reader: R) -> ::std::io::Result<Vec<String>> {
+  let mut contents = String::new();
+  reader.read_to_string(&mut contents)?;
+
+  Ok(
+    contents.lines()
+      .filter(|line| !line.trim().is_empty())
+      .map(|line| line.to_string())
+      .collect()
+  )
+}
+
 /// The "file"'s name might be '-', in which case it refers to
 /// `stdin()`.
 fn open_file(file: String) -> Result<File> {
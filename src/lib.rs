@@ -2,12 +2,17 @@
 
 #[macro_use] extern crate error_chain;
 extern crate regex;
+#[cfg(feature = "serde")] extern crate serde;
 
 pub mod input;
 pub mod list;
+pub mod plan;
+pub mod report;
 
 mod parsing;
 
+pub use parsing::AnchorSyntax;
+
 pub mod processing_errors {
   error_chain! {
     errors {
@@ -30,36 +35,282 @@ pub mod processing_errors {
         description("nonexistent tag name")
         display("warn: '{}', line {}: nonexistent tag name: '{}'", file, lineno, tag)
       }
+
+      ReservedName(file: String, lineno: usize, tag: String) {
+        description("label uses a reserved name")
+        display("warn: '{}', line {}: label uses a reserved name: '{}'", file, lineno, tag)
+      }
+
+      MissingLabel(tag: String) {
+        description("requested label does not exist")
+        display("error: no label named '{}'", tag)
+      }
+
+      RecursiveLabel(tag: String) {
+        description("label includes itself")
+        display("warn: label includes itself, not expanding it again: '{}'", tag)
+      }
+
+      LineTooLong(lineno: usize, length: usize) {
+        description("output line is too long")
+        display("warn: output line {}: line is too long ({} columns)", lineno, length)
+      }
+
+      UnknownRevision(reason: String) {
+        description("could not find the git revision")
+        display("warn: could not find the git revision: {}", reason)
+      }
+
+      WriteFailed(reason: String) {
+        description("could not write output")
+        display("error: could not write output: {}", reason)
+      }
     }
   } 
+
+  /// The names used to refer to each kind of error, e.g. with `--allow`.
+  pub const KIND_NAMES: &[&str] = &[
+    "not-utf8",
+    "malformed-anchor",
+    "duplicate-anchor",
+    "missing-tag",
+    "reserved-name",
+    "missing-label",
+    "recursive-label",
+    "line-too-long",
+    "unknown-revision",
+    "write-failed"
+  ];
+
+  /// The name of the given kind of error, if it has one.
+  pub fn kind_name(kind: &ErrorKind) -> Option<&'static str> {
+    match *kind {
+      ErrorKind::NotUTF8(..) => Some("not-utf8"),
+      ErrorKind::MalformedAnchor(..) => Some("malformed-anchor"),
+      ErrorKind::DuplicateAnchor(..) => Some("duplicate-anchor"),
+      ErrorKind::MissingTag(..) => Some("missing-tag"),
+      ErrorKind::ReservedName(..) => Some("reserved-name"),
+      ErrorKind::MissingLabel(..) => Some("missing-label"),
+      ErrorKind::RecursiveLabel(..) => Some("recursive-label"),
+      ErrorKind::LineTooLong(..) => Some("line-too-long"),
+      ErrorKind::UnknownRevision(..) => Some("unknown-revision"),
+      ErrorKind::WriteFailed(..) => Some("write-failed"),
+      ErrorKind::Msg(_) => None
+    }
+  }
+
+  /// How serious a kind of error is.
+  #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+  pub enum Severity {
+    Error,
+    Warning
+  }
+
+  /// The severity of the given kind of error. Warnings don't stop the
+  /// output from being produced, but might make it differ from what was
+  /// intended.
+  pub fn severity(kind: &ErrorKind) -> Severity {
+    match *kind {
+      ErrorKind::MalformedAnchor(..) |
+      ErrorKind::DuplicateAnchor(..) |
+      ErrorKind::MissingTag(..) |
+      ErrorKind::ReservedName(..) |
+      ErrorKind::RecursiveLabel(..) |
+      ErrorKind::LineTooLong(..) |
+      ErrorKind::UnknownRevision(..) => Severity::Warning,
+      ErrorKind::NotUTF8(..) |
+      ErrorKind::MissingLabel(..) |
+      ErrorKind::WriteFailed(..) |
+      ErrorKind::Msg(_) => Severity::Error
+    }
+  }
+
+  /// Remove all errors whose kind is named in `allowed`.
+  pub fn filter_allowed(errors: Vec<Error>, allowed: &[String]) -> Vec<Error> {
+    errors.into_iter()
+      .filter(|error| match kind_name(error.kind()) {
+        Some(name) => !allowed.iter().any(|allow| allow == name),
+        None => true
+      })
+      .collect()
+  }
 }
 
 use std::rc::Rc;
 use std::io;
+use std::mem;
 use std::result;
 use std::default::Default;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use input::File;
 use list::List;
 
+/// How the indentation of lines within a block is treated when the block
+/// is placed at a label.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BlockIndentation {
+  /// Keep each line's indentation as written, on top of the label's.
+  AsWritten,
+  /// Remove the indentation shared by every non-blank line of the block,
+  /// so that the least indented lines line up with the label.
+  RelativeToMinimum,
+  /// Remove the indentation of the block's first non-blank line from every
+  /// line, so that the first line lines up with the label. Lines indented
+  /// less than the first line lose all of their indentation.
+  RelativeToFirstLine
+}
+
 pub struct OutputOptions {
-  pub comment: Option<String>
+  pub comment: Option<String>,
+  /// A line to place between consecutive blocks in the output.
+  pub block_separator: Option<String>,
+  /// Which syntaxes to recognize anchors in. Each line is checked against
+  /// them in order, and the first one which matches is used.
+  pub anchor_syntaxes: Vec<AnchorSyntax>,
+  /// Names which labels shouldn't use. Defining a label with one of these
+  /// names produces a warning.
+  pub reserved_names: BTreeSet<String>,
+  /// How many columns a tab counts for when working out the indentation
+  /// of a label.
+  pub tab_size: usize,
+  /// Put a blank line in the output wherever an anchor line was removed,
+  /// so that output line numbers match the input's. Only takes effect when
+  /// tangling a single file where no blocks were moved.
+  pub keep_source_line_gaps: bool,
+  /// Warn about any output lines longer than this many columns.
+  pub max_line_length: Option<usize>,
+  /// Add each error and warning to the end of the output as a comment.
+  /// Only takes effect when `comment` is set.
+  pub embed_diagnostics: bool,
+  /// Other names that labels can be referred to by, mapped to the name
+  /// of the label. Only affects references to labels, not definitions.
+  pub aliases: BTreeMap<String, String>,
+  /// Process the inputs in order of their names, rather than in the order
+  /// they were given, so that the output doesn't depend on the latter.
+  pub sort_inputs: bool,
+  /// Remove this from the start of each line that begins with it, for
+  /// when the code is written inside comments.
+  pub strip_prefix: Option<String>,
+  /// Split block lines containing newlines into separate output lines,
+  /// each indented the same way.
+  pub split_embedded_newlines: bool,
+  /// Only put a header before blocks with at least this many lines, when
+  /// `comment` is set, so that short blocks aren't mostly comments.
+  pub header_min_block_lines: usize,
+  /// Remove any blank lines from the end of the output, so that it ends
+  /// with exactly one newline once each line is written out.
+  pub normalize_trailing: bool,
+  /// How to treat the indentation of lines within each block.
+  pub block_indentation: BlockIndentation,
+  /// Drop the first line of each input if it starts with `#!`, so that
+  /// the shebangs of script fragments don't end up in the middle of the
+  /// output.
+  pub skip_leading_shebang: bool,
+  /// Keep the shebang of the first input anyway, when
+  /// `skip_leading_shebang` is set, so that the output still has one.
+  pub keep_first_shebang: bool,
+  /// End the output of `tangle_to_string()` with a newline after the last
+  /// line, as it would be when written out a line at a time.
+  pub final_newline: bool,
+  /// Place blocks inserted before the same label in the order they were
+  /// seen, rather than the first one seen coming last.
+  pub before_in_order: bool
 }
 
 impl Default for OutputOptions {
   fn default() -> Self {
     OutputOptions {
-      comment: None
+      comment: None,
+      block_separator: None,
+      anchor_syntaxes: vec![AnchorSyntax::Native],
+      reserved_names: BTreeSet::new(),
+      tab_size: 8,
+      keep_source_line_gaps: false,
+      max_line_length: None,
+      embed_diagnostics: false,
+      aliases: BTreeMap::new(),
+      sort_inputs: false,
+      strip_prefix: None,
+      split_embedded_newlines: false,
+      header_min_block_lines: 0,
+      normalize_trailing: false,
+      block_indentation: BlockIndentation::AsWritten,
+      skip_leading_shebang: false,
+      keep_first_shebang: false,
+      final_newline: false,
+      before_in_order: false
     }
   }
 }
 
+/// The result of tangling: the output lines, along with any errors found
+/// while producing them.
+pub struct TangleOutput {
+  pub lines: Vec<String>,
+  pub errors: Vec<processing_errors::Error>
+}
+
+impl TangleOutput {
+  /// Write out each line, followed by a newline.
+  pub fn write_to<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+    for line in &self.lines {
+      writeln!(writer, "{}", line)?;
+    }
+
+    Ok(())
+  }
+
+  /// The lines as they would be written out by `write_to()`, each one
+  /// followed by a newline. Same as `tangle_to_string()` with
+  /// `final_newline` set.
+  pub fn into_string(self) -> String {
+    join_lines(&self.lines, true)
+  }
+
+  /// How many lines of output there are.
+  pub fn line_count(&self) -> usize {
+    self.lines.len()
+  }
+
+  /// Check if any errors were found. Warnings alone don't count.
+  pub fn has_errors(&self) -> bool {
+    use processing_errors::{severity, Severity};
+
+    self.errors.iter().any(|error| severity(error.kind()) == Severity::Error)
+  }
+}
+
+impl From<TangleOutput> for (Vec<String>, Vec<processing_errors::Error>) {
+  /// Split the output back up into lines and errors, as `tangle_output()`
+  /// used to return them.
+  fn from(output: TangleOutput) -> Self {
+    (output.lines, output.errors)
+  }
+}
+
+/// Statistics gathered while tangling, for reporting on the structure
+/// of a literate project.
+#[derive(Default)]
+pub struct Stats {
+  /// The names of the input files, in the order they were processed.
+  pub files: Vec<String>,
+  /// How many lines of output were produced.
+  pub output_lines: usize,
+  /// How many anchors used each command, e.g. `"after"`.
+  pub anchor_counts: BTreeMap<String, usize>,
+  /// How many `before`/`after`/`replace`/`delete`/`duplicate` anchors
+  /// targeted each label. A label which is never referenced has a count
+  /// of zero.
+  pub label_references: BTreeMap<String, usize>
+}
+
 struct Block {
   lines: Vec<String>,
   file: Rc<String>,
-  lineno: usize
+  lineno: usize,
+  raw: bool  // From a `raw` region, so output exactly as written.
 }
 
 impl Block {
@@ -67,46 +318,339 @@ impl Block {
     Block {
       lines: Vec::new(),
       file: file,
-      lineno: lineno
+      lineno: lineno,
+      raw: false
     }
   }
 }
 
+#[derive(Default)]
 struct Anchor {
   indentation: usize,  // The *absolute* level of indentation.
-  tangled: Tangled
+  tangled: Tangled,
+  before_len: usize  // How much of `tangled` was placed by `before` anchors.
 }
 
 impl Anchor {
   fn new(indentation: usize) -> Self {
     Anchor {
       indentation: indentation,
-      tangled: List::new()
+      ..Default::default()
     }
   }
 }
 
-/// An `Either` represents the situation when *either* arm is a valid
-/// value, as opposed to a `Result`, where one arm designates an error.
-enum Either<T, U> {
-  Left(T),
-  Right(U)
+#[derive(Clone)]
+struct AnchorRef(String);
+
+/// A comment to place in the output, from a `comment` anchor.
+struct Comment {
+  indentation: usize,  // Relative to the surrounding lines.
+  text: String
 }
 
-struct AnchorRef(String);
+/// A list of every label defined in the inputs, from a `toc` anchor. The
+/// entries are only filled in once all the inputs have been read.
+struct Toc {
+  indentation: usize,  // Relative to the surrounding lines.
+  entries: Vec<String>
+}
+
+/// What a `timestamp` or `revision` anchor expands to.
+#[derive(Clone, Copy)]
+enum StampKind {
+  Timestamp,  // The current UTC time.
+  Revision  // The short hash of git's `HEAD`.
+}
+
+/// A line worked out when the output is produced, from a `timestamp` or
+/// `revision` anchor.
+struct Stamp {
+  kind: StampKind,
+  indentation: usize  // Relative to the surrounding lines.
+}
+
+/// A single piece of tangled output.
+enum Knot {
+  Block(Block),
+  Anchor(AnchorRef),
+  Comment(Comment),
+  Duplicate(AnchorRef, usize),  // With the indentation of the anchor.
+  Toc(Toc),
+  Stamp(Stamp),
+  Gap  // Where an anchor line was removed.
+}
+
+type Tangled = List<Knot>;
 
-type Tangled = List<Either<Block, AnchorRef>>;
+/// For each label name, the file each definition of it appeared in and the
+/// key its contents are stored under, in the order they were defined.
+type Definitions = BTreeMap<String, Vec<(Rc<String>, String)>>;
 
+#[derive(Clone)]
 enum OutputTarget {
   Insert,
   Before(AnchorRef),
-  After(AnchorRef)
+  After(AnchorRef),
+  Replace(AnchorRef)
 }
 
 /// Process all the literate programming directives in the contents of the
-/// given files, return a Vec of output lines (suitable for immediate
-/// printing to, say, `stdout`)
-pub fn tangle_output(inputs: Vec<File>, options: OutputOptions) -> (Vec<String>, Vec<processing_errors::Error>) {
+/// given files, return the output lines (suitable for immediate printing
+/// to, say, `stdout`) along with any errors found.
+pub fn tangle_output(inputs: Vec<File>, options: OutputOptions) -> TangleOutput {
+  let (lines, errors, _) = tangle_output_with_stats(inputs, options);
+  TangleOutput { lines, errors }
+}
+
+/// Same as `tangle_output()`, but also return statistics about the anchors
+/// encountered while processing.
+pub fn tangle_output_with_stats(inputs: Vec<File>, options: OutputOptions) -> (Vec<String>, Vec<processing_errors::Error>, Stats) {
+  Tangler::new(inputs, &options).tangle_output(options)
+}
+
+/// Same as `tangle_output()`, but write each line to `writer` as soon as
+/// it's produced instead of collecting them all first. Returns the number
+/// of lines written.
+pub fn tangle_to_writer<W: io::Write>(inputs: Vec<File>, options: OutputOptions, writer: W) -> (usize, Vec<processing_errors::Error>) {
+  let (written, errors, _) = Tangler::new(inputs, &options).write_output(options, writer);
+  (written, errors)
+}
+
+/// Same as `tangle_output()`, but join the output lines into a single
+/// string, separated by newlines. Unlike `TangleOutput::into_string()`,
+/// the last line only ends with a newline if `options.final_newline` is
+/// set.
+pub fn tangle_to_string(inputs: Vec<File>, options: OutputOptions) -> (String, Vec<processing_errors::Error>) {
+  let final_newline = options.final_newline;
+  let output = tangle_output(inputs, options);
+
+  (join_lines(&output.lines, final_newline), output.errors)
+}
+
+/// Join lines of output into a single string, separated by newlines, with
+/// one after the last line as well if `final_newline` is set.
+fn join_lines(lines: &[String], final_newline: bool) -> String {
+  let mut output = lines.join("\n");
+  if final_newline && !output.is_empty() {
+    output.push('\n');
+  }
+
+  output
+}
+
+/// Same as `tangle_to_string()`, but with `content` as the only input,
+/// named `<string>` in any errors.
+pub fn tangle_str(content: &str, options: OutputOptions) -> (String, Vec<processing_errors::Error>) {
+  let input = File {
+    name: "<string>".to_string(),
+    contents: Box::new(io::Cursor::new(content.as_bytes().to_vec()))
+  };
+
+  tangle_to_string(vec![input], options)
+}
+
+/// Same as `tangle_output()`, but only output the contents of the given
+/// label, as if they had been inserted at the top level. Everything
+/// outside of the label is ignored.
+pub fn tangle_label(inputs: Vec<File>, options: OutputOptions, label: &str) -> (Vec<String>, Vec<processing_errors::Error>) {
+  let (output, errors, _) = tangle_label_with_stats(inputs, options, label);
+  (output, errors)
+}
+
+/// Same as `tangle_label()`, but also return statistics about the anchors
+/// encountered while processing.
+pub fn tangle_label_with_stats(inputs: Vec<File>, options: OutputOptions, label: &str) -> (Vec<String>, Vec<processing_errors::Error>, Stats) {
+  Tangler::new(inputs, &options).tangle_label(options, label)
+}
+
+/// Check whether the file `target` already contains exactly the given
+/// output, as it would be written out with a newline after each line. A
+/// file which doesn't exist never matches.
+pub fn output_matches_file(output: &[String], target: &str) -> io::Result<bool> {
+  use std::fs;
+
+  let contents = match fs::read(target) {
+    Ok(contents) => contents,
+    Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+    Err(err) => return Err(err)
+  };
+
+  let mut written = Vec::new();
+  for line in output {
+    written.extend_from_slice(line.as_bytes());
+    written.push(b'\n');
+  }
+
+  Ok(contents == written)
+}
+
+/// The structure of a set of inputs: where each block of lines should go,
+/// after reading through the inputs but before producing any output.
+/// Can be saved and loaded back with the functions in `plan`.
+pub struct Tangler {
+  tangled: Tangled,
+  anchors: BTreeMap<String, Anchor>,
+  definitions: Definitions,
+  errors: Vec<processing_errors::Error>,
+  stats: Stats
+}
+
+impl Tangler {
+  /// Read through all the inputs, collecting any errors found.
+  pub fn new(inputs: Vec<File>, options: &OutputOptions) -> Self {
+    let (tangled, anchors, definitions, errors, stats) = tangle_sections(inputs, options);
+
+    Tangler {
+      tangled,
+      anchors,
+      definitions,
+      errors,
+      stats
+    }
+  }
+
+  /// Describe where each block of lines has been placed, one line of
+  /// description per line of the result. Useful for debugging why the
+  /// output isn't what was expected.
+  pub fn dump_structure(&self) -> Vec<String> {
+    let mut dump = vec!["top level:".to_string()];
+    dump_tangled(&self.tangled, &mut dump);
+
+    for (name, anchor) in &self.anchors {
+      dump.push(format!("label '{}', indentation {}:", name, anchor.indentation));
+      dump_tangled(&anchor.tangled, &mut dump);
+    }
+
+    dump
+  }
+
+  /// Produce the output, as with `tangle_output_with_stats()`.
+  pub fn tangle_output(self, options: OutputOptions) -> (Vec<String>, Vec<processing_errors::Error>, Stats) {
+    let mut buffer = Vec::new();
+    let (_, errors, stats) = self.write_output(options, &mut buffer);
+
+    (buffered_lines(buffer), errors, stats)
+  }
+
+  /// Produce the output for a single label, as with
+  /// `tangle_label_with_stats()`.
+  pub fn tangle_label(self, options: OutputOptions, label: &str) -> (Vec<String>, Vec<processing_errors::Error>, Stats) {
+    let mut buffer = Vec::new();
+    let (_, errors, stats) = self.write_label(options, label, &mut buffer);
+
+    (buffered_lines(buffer), errors, stats)
+  }
+
+  /// Write the output to `writer` as it's produced, as with
+  /// `tangle_to_writer()`, returning the number of lines written.
+  pub fn write_output<W: io::Write>(self, options: OutputOptions, writer: W) -> (usize, Vec<processing_errors::Error>, Stats) {
+    let Tangler { tangled, anchors, mut errors, mut stats, .. } = self;
+
+    let keep_gaps = options.keep_source_line_gaps && !moved_blocks(&stats);
+    let written = write_tangled_output(&tangled, &anchors, None, &options, keep_gaps, &mut errors, writer);
+    stats.output_lines = written;
+
+    (written, errors, stats)
+  }
+
+  /// Write the output for a single label to `writer` as it's produced,
+  /// returning the number of lines written.
+  pub fn write_label<W: io::Write>(self, options: OutputOptions, label: &str, writer: W) -> (usize, Vec<processing_errors::Error>, Stats) {
+    use processing_errors::ErrorKind;
+
+    let Tangler { anchors, definitions, mut errors, mut stats, .. } = self;
+
+    // Labels are looked up the same way references to them are, so that
+    // they can be qualified with the file they were defined in.
+    let key = resolve_label(&definitions, label);
+    let empty = List::new();
+    let tangled = match key.as_ref().and_then(|key| anchors.get(key)) {
+      Some(anchor) => &anchor.tangled,
+      None => {
+        errors.push(ErrorKind::MissingLabel(label.to_string()).into());
+        &empty
+      }
+    };
+    let within = key.as_ref().map_or(label, |key| key.as_str());
+    let written = write_tangled_output(tangled, &anchors, Some(within), &options, false, &mut errors, writer);
+    stats.output_lines = written;
+
+    (written, errors, stats)
+  }
+}
+
+/// Split output written to a buffer back up into lines.
+fn buffered_lines(buffer: Vec<u8>) -> Vec<String> {
+  String::from_utf8(buffer)
+    .expect("invariant violated: output is not UTF-8")
+    .split_terminator('\n')
+    .map(|line| line.to_string())
+    .collect()
+}
+
+fn dump_tangled(tangled: &Tangled, dump: &mut Vec<String>) {
+  for knot in tangled.iter() {
+    let description = match *knot {
+      Knot::Block(ref block) => {
+        let count = block.lines.len();
+        let plural = if count == 1 { "" } else { "s" };
+        let kind = if block.raw { "raw block" } else { "block" };
+        format!("{} '{}', line {} ({} line{})", kind, block.file, block.lineno, count, plural)
+      },
+      Knot::Anchor(AnchorRef(ref name)) => format!("label '{}'", name),
+      Knot::Duplicate(AnchorRef(ref name), _) => format!("duplicate of label '{}'", name),
+      Knot::Comment(ref comment) => format!("comment '{}'", comment.text),
+      Knot::Toc(ref toc) => format!("table of contents ({} labels)", toc.entries.len()),
+      Knot::Stamp(ref stamp) => match stamp.kind {
+        StampKind::Timestamp => "timestamp".to_string(),
+        StampKind::Revision => "revision".to_string()
+      },
+      Knot::Gap => "gap".to_string()
+    };
+
+    dump.push(format!("  {}", description));
+  }
+}
+
+/// Replace the label name that an anchor refers to with the name it's an
+/// alias for, if it is one.
+fn resolve_alias(anchor: parsing::Anchor, aliases: &BTreeMap<String, String>) -> parsing::Anchor {
+  use parsing::Anchor;
+
+  let canonical = |name: String| aliases.get(&name).cloned().unwrap_or(name);
+
+  match anchor {
+    Anchor::Before(name) => Anchor::Before(canonical(name)),
+    Anchor::After(name) => Anchor::After(canonical(name)),
+    Anchor::Replace(name) => Anchor::Replace(canonical(name)),
+    Anchor::Delete(name) => Anchor::Delete(canonical(name)),
+    Anchor::Duplicate(name) => Anchor::Duplicate(canonical(name)),
+    anchor => anchor
+  }
+}
+
+/// Find the key that the contents of the referenced label are stored
+/// under in the anchor map. A reference can be qualified with the file the
+/// label was defined in, as in `file.rs:name`, to choose between labels
+/// with the same name; otherwise, the latest definition is used.
+fn resolve_label(definitions: &Definitions, reference: &str) -> Option<String> {
+  let (file, name) = parsing::split_qualified(reference);
+
+  definitions.get(name)?
+    .iter()
+    .rev()
+    .find(|(defined_in, _)| match file {
+      Some(file) => defined_in.as_str() == file || defined_in.ends_with(&format!("/{}", file)),
+      None => true
+    })
+    .map(|(_, key)| key.clone())
+}
+
+/// Read through all the inputs, splitting them up into blocks and placing
+/// each one where its anchors say it should go. Returns the top-level
+/// output along with the contents of each label, still unresolved.
+fn tangle_sections(mut inputs: Vec<File>, options: &OutputOptions) -> (Tangled, BTreeMap<String, Anchor>, Definitions, Vec<processing_errors::Error>, Stats) {
   use std::io::{BufReader, BufRead};
 
   use parsing::Anchor;
@@ -114,19 +658,43 @@ pub fn tangle_output(inputs: Vec<File>, options: OutputOptions) -> (Vec<String>,
 
   let mut tangled = List::new();
   let mut anchors = BTreeMap::new();
+  let mut definitions = BTreeMap::new();
+  let mut locations = Vec::new();  // Where each label was defined, for `toc`.
   let mut errors = Vec::new();  // Errors that we accrue during processing.
+  let mut stats = Stats::default();
 
-  for input in inputs {
-    let filename = Rc::new(input.name);
+  if options.sort_inputs {
+    inputs.sort_by(|a, b| a.name.cmp(&b.name));
+  }
+
+  for (index, input) in inputs.into_iter().enumerate() {
+    stats.files.push(input.name.clone());
+
+    let mut filename = Rc::new(input.name);
+    let mut line_base = 0;  // Reset by `source` anchors within the file.
 
     let mut lines = BufReader::new(input.contents)
       .lines()
       .enumerate()
-      .map(|(lineno, line)| (lineno + 1, line));
+      .map(|(lineno, line)| (lineno + 1, line))
+      .peekable();
     let mut state = OutputTarget::Insert;
     let mut tangled_section = List::new();
     let mut block = Block::new(filename.clone(), 1);
 
+    let keep_shebang = !options.skip_leading_shebang || (options.keep_first_shebang && index == 0);
+    let has_shebang = match lines.peek() {
+      Some(&(_, Ok(ref line))) => line.starts_with("#!"),
+      _ => false
+    };
+    if has_shebang && !keep_shebang {
+      lines.next();
+      block = Block::new(filename.clone(), 2);
+      if options.keep_source_line_gaps {
+        tangled_section.push_back(Knot::Gap);
+      }
+    }
+
     macro_rules! emplace_section {
       () => {
         match state {
@@ -134,51 +702,82 @@ pub fn tangle_output(inputs: Vec<File>, options: OutputOptions) -> (Vec<String>,
           OutputTarget::Before(AnchorRef(anchor_name)) => {
             let anchor: &mut ::Anchor = anchors.get_mut(&anchor_name)
               .expect("invariant violated: anchor name does not exist");
-            anchor.tangled.append_front(&mut tangled_section);
+            if options.before_in_order {
+              // Still in front of everything placed after the label, but
+              // behind what earlier `before` anchors placed.
+              let len = tangled_section.len();
+              anchor.tangled.splice_before(anchor.before_len, &mut tangled_section);
+              anchor.before_len += len;
+            } else {
+              anchor.tangled.append_front(&mut tangled_section);
+            }
           },
           OutputTarget::After(AnchorRef(anchor_name)) => {
             let anchor: &mut ::Anchor = anchors.get_mut(&anchor_name)
               .expect("invariant violated: anchor name does not exist");
             anchor.tangled.append_back(&mut tangled_section);
+          },
+          OutputTarget::Replace(AnchorRef(anchor_name)) => {
+            let anchor: &mut ::Anchor = anchors.get_mut(&anchor_name)
+              .expect("invariant violated: anchor name does not exist");
+            anchor.tangled = List::new();
+            anchor.tangled.append_back(&mut tangled_section);
+            anchor.before_len = 0;
           }
         }
       }
     }
 
     loop {
-      let next_anchor = process_block_lines(&mut lines, &mut block, &mut errors);
+      let next_anchor = process_block_lines(
+        &mut lines,
+        line_base,
+        options,
+        &mut block,
+        &mut errors
+      );
 
       if !block.lines.is_empty() {
-        tangled_section.push_back(Either::Left(block));
+        tangled_section.push_back(Knot::Block(block));
       }
 
       match next_anchor {
         Some((lineno, indentation, anchor)) => {
-          macro_rules! has_anchor {
+          macro_rules! find_anchor {
             ($anchor_name:expr) => {{
-              if anchors.contains_key($anchor_name) {
-                true
-              } else {
-                let filename: &String = &filename;
-                let error = ErrorKind::MissingTag(filename.clone(), lineno, $anchor_name.clone()).into();
-                errors.push(error);
-                false
+              match resolve_label(&definitions, $anchor_name) {
+                Some(key) => {
+                  let (_, name) = parsing::split_qualified($anchor_name);
+                  *stats.label_references.entry(name.to_string()).or_insert(0) += 1;
+                  Some(key)
+                },
+                None => {
+                  let filename: &String = &filename;
+                  let error = ErrorKind::MissingTag(filename.clone(), lineno, $anchor_name.clone()).into();
+                  errors.push(error);
+                  None
+                }
               }
             }}
           }
 
+          *stats.anchor_counts.entry(anchor.op_name().to_string()).or_insert(0) += 1;
+
           block = Block::new(filename.clone(), lineno);
-          match anchor {
+          match resolve_alias(anchor, &options.aliases) {
             Anchor::Insert => {
               emplace_section!();
               tangled_section = List::new();
               state = OutputTarget::Insert;
+              if options.keep_source_line_gaps {
+                tangled_section.push_back(Knot::Gap);
+              }
             },
             Anchor::Before(anchor_name) => {
               emplace_section!();
               tangled_section = List::new();
-              if has_anchor!(&anchor_name) {
-                state = OutputTarget::Before(AnchorRef(anchor_name));
+              if let Some(key) = find_anchor!(&anchor_name) {
+                state = OutputTarget::Before(AnchorRef(key));
               } else {
                 state = OutputTarget::Insert;
               }
@@ -186,16 +785,114 @@ pub fn tangle_output(inputs: Vec<File>, options: OutputOptions) -> (Vec<String>,
             Anchor::After(anchor_name) => {
               emplace_section!();
               tangled_section = List::new();
-              if has_anchor!(&anchor_name) {
-                state = OutputTarget::After(AnchorRef(anchor_name));
+              if let Some(key) = find_anchor!(&anchor_name) {
+                state = OutputTarget::After(AnchorRef(key));
               } else {
                 state = OutputTarget::Insert;
               }
             },
             Anchor::Label(anchor_name) => {
+              if options.reserved_names.contains(&anchor_name) {
+                let filename: &String = &filename;
+                let error = ErrorKind::ReservedName(filename.clone(), lineno, anchor_name.clone()).into();
+                errors.push(error);
+              }
+
+              // Labels with the same name can be defined in several places,
+              // so all but the first need a key of their own.
+              let key = if anchors.contains_key(&anchor_name) {
+                format!("{}:{}:{}", filename, lineno, anchor_name)
+              } else {
+                anchor_name.clone()
+              };
+
               let anchor = ::Anchor::new(indentation);
-              anchors.insert(anchor_name.clone(), anchor);
-              tangled_section.push_back(Either::Right(AnchorRef(anchor_name)));
+              anchors.insert(key.clone(), anchor);
+              definitions.entry(anchor_name.clone())
+                .or_insert_with(Vec::new)
+                .push((filename.clone(), key.clone()));
+              locations.push((anchor_name.clone(), filename.clone(), lineno));
+              stats.label_references.entry(anchor_name).or_insert(0);
+              if options.keep_source_line_gaps {
+                tangled_section.push_back(Knot::Gap);
+              }
+              // The label goes into the current section like any other
+              // block, so its contents end up between the lines around it.
+              tangled_section.push_back(Knot::Anchor(AnchorRef(key)));
+            },
+            Anchor::Replace(anchor_name) => {
+              emplace_section!();
+              tangled_section = List::new();
+              if let Some(key) = find_anchor!(&anchor_name) {
+                state = OutputTarget::Replace(AnchorRef(key));
+              } else {
+                state = OutputTarget::Insert;
+              }
+            },
+            Anchor::Delete(anchor_name) => {
+              // Lines before the anchor have to be placed first, so that
+              // any going to the label are thrown away along with the rest.
+              let target = state.clone();
+              emplace_section!();
+              tangled_section = List::new();
+              state = target;
+              if let Some(key) = find_anchor!(&anchor_name) {
+                let anchor: &mut ::Anchor = anchors.get_mut(&key)
+                  .expect("invariant violated: anchor name does not exist");
+                anchor.tangled = List::new();
+                anchor.before_len = 0;
+              }
+            },
+            Anchor::Duplicate(anchor_name) => {
+              if let Some(key) = find_anchor!(&anchor_name) {
+                tangled_section.push_back(Knot::Duplicate(AnchorRef(key), indentation));
+              }
+            },
+            Anchor::Comment(text) => {
+              let comment = Comment { indentation, text };
+              tangled_section.push_back(Knot::Comment(comment));
+            },
+            Anchor::Toc => {
+              let toc = Toc { indentation, entries: Vec::new() };
+              tangled_section.push_back(Knot::Toc(toc));
+            },
+            Anchor::Timestamp => {
+              let stamp = Stamp { kind: StampKind::Timestamp, indentation };
+              tangled_section.push_back(Knot::Stamp(stamp));
+            },
+            Anchor::Revision => {
+              let stamp = Stamp { kind: StampKind::Revision, indentation };
+              tangled_section.push_back(Knot::Stamp(stamp));
+            },
+            Anchor::Raw => {
+              // Everything up to the matching `endraw` goes into the output
+              // exactly as written, even if it looks like an anchor.
+              let mut raw = Block::new(filename.clone(), lineno);
+              raw.raw = true;
+              let end = process_raw_lines(&mut lines, line_base, options, &mut raw, &mut errors);
+
+              if options.keep_source_line_gaps {
+                tangled_section.push_back(Knot::Gap);
+              }
+              tangled_section.push_back(Knot::Block(raw));
+              if options.keep_source_line_gaps && end.is_some() {
+                tangled_section.push_back(Knot::Gap);  // For the `endraw` anchor.
+              }
+              block = Block::new(filename.clone(), end.unwrap_or(lineno));
+            },
+            Anchor::EndRaw => {
+              // Only means anything after a `raw` anchor, where it's
+              // handled by `process_raw_lines()`.
+            },
+            Anchor::Source(source_name) => {
+              // Everything after this point behaves as if it were the start
+              // of a new file with the given name.
+              emplace_section!();
+              tangled_section = List::new();
+              state = OutputTarget::Insert;
+              filename = Rc::new(source_name);
+              line_base += lineno;
+              block = Block::new(filename.clone(), 1);
             }
           };
         },
@@ -206,20 +903,197 @@ pub fn tangle_output(inputs: Vec<File>, options: OutputOptions) -> (Vec<String>,
       };
     }
   }
-  
-  (collect_tangled_output(tangled, anchors, options), errors)
+
+  // Labels can be defined after a `toc` anchor, so the entries can only be
+  // filled in now.
+  if stats.anchor_counts.contains_key("toc") {
+    locations.sort_by(|a, b| a.0.cmp(&b.0));
+    let entries: Vec<String> = locations.into_iter()
+      .map(|(name, file, lineno)| format!("{}: '{}', line {}", name, file, lineno))
+      .collect();
+
+    let sections = Some(&mut tangled).into_iter()
+      .chain(anchors.values_mut().map(|anchor| &mut anchor.tangled));
+    for section in sections {
+      for knot in section.iter_mut() {
+        if let Knot::Toc(ref mut toc) = *knot {
+          toc.entries = entries.clone();
+        }
+      }
+    }
+  }
+
+  (tangled, anchors, definitions, errors, stats)
+}
+
+/// Whether any blocks might have ended up somewhere other than where they
+/// were in the input, so that line numbers can no longer match up.
+fn moved_blocks(stats: &Stats) -> bool {
+  stats.files.len() != 1 ||
+    ["before", "after", "replace", "delete", "duplicate", "source", "toc"].iter().any(|op| stats.anchor_counts.contains_key(*op))
 }
 
-fn collect_tangled_output(tangled: Tangled, 
-                          mut anchors: BTreeMap<String, Anchor>,
-                          options: OutputOptions) -> Vec<String> 
+/// Collect the lines of `tangled`, expanding any labels it refers to.
+/// `within` is the label that `tangled` belongs to, if any.
+fn write_tangled_output<W: io::Write>(tangled: &Tangled,
+                                      anchors: &BTreeMap<String, Anchor>,
+                                      within: Option<&str>,
+                                      options: &OutputOptions,
+                                      keep_gaps: bool,
+                                      errors: &mut Vec<processing_errors::Error>,
+                                      writer: W) -> usize
 {
-  let mut lines = Vec::new();
-  collect_anchor_lines(tangled, &mut anchors, &mut lines, 0, &options);
-  lines
+  let mut collector = Collector {
+    anchors,
+    options,
+    keep_gaps,
+    output: OutputWriter::new(writer, options),
+    emitted_block: false,
+    expanding: within.into_iter().map(|label| label.to_string()).collect(),
+    revision: None,
+    errors: Vec::new()
+  };
+
+  collector.collect_anchor_lines(tangled, 0);
+
+  let Collector { mut output, errors: mut collected, .. } = collector;
+  errors.append(&mut collected);
+  errors.append(&mut output.end_lines());
+  embed_diagnostics(&mut output, errors, options);
+
+  let (written, mut write_errors) = output.finish();
+  errors.append(&mut write_errors);
+  written
+}
+
+/// How many bytes of leading whitespace to remove from each line of a
+/// block, at most, for the given indentation mode.
+fn block_dedent(lines: &[&str], mode: BlockIndentation) -> usize {
+  let indented = |line: &&str| line.len() - trim_indentation(line).len();
+  let mut non_blank = lines.iter().filter(|line| !line.trim().is_empty());
+
+  match mode {
+    BlockIndentation::AsWritten => 0,
+    BlockIndentation::RelativeToMinimum => non_blank.map(indented).min().unwrap_or(0),
+    BlockIndentation::RelativeToFirstLine => non_blank.next().map(indented).unwrap_or(0)
+  }
+}
+
+/// The line without `prefix` at the start of it, if it begins with it.
+fn without_prefix<'a>(line: &'a str, prefix: Option<&String>) -> &'a str {
+  match prefix {
+    Some(prefix) if line.starts_with(prefix.as_str()) => &line[prefix.len()..],
+    _ => line
+  }
+}
+
+/// The line without its leading spaces and tabs.
+fn trim_indentation(line: &str) -> &str {
+  line.trim_start_matches(&[' ', '\t'][..])
+}
+
+/// Where lines of output go as they're produced. Each line is checked
+/// against `options.max_line_length` on the way through, and blank lines
+/// are held back until something follows them, so that the ones at the
+/// end can be dropped if `options.normalize_trailing` is set.
+struct OutputWriter<'a, W> {
+  writer: W,
+  options: &'a OutputOptions,
+  held_blank: Vec<String>,
+  lineno: usize,
+  written: usize,
+  /// Set once a write fails, after which nothing more is written.
+  failed: bool,
+  errors: Vec<processing_errors::Error>
+}
+
+impl<'a, W: io::Write> OutputWriter<'a, W> {
+  fn new(writer: W, options: &'a OutputOptions) -> Self {
+    OutputWriter {
+      writer,
+      options,
+      held_blank: Vec::new(),
+      lineno: 0,
+      written: 0,
+      failed: false,
+      errors: Vec::new()
+    }
+  }
+
+  /// Output a line produced from the inputs.
+  fn push(&mut self, line: String) {
+    if self.options.normalize_trailing && line.trim().is_empty() {
+      self.held_blank.push(line);
+      return;
+    }
+
+    for blank in mem::take(&mut self.held_blank) {
+      self.check_and_write(&blank);
+    }
+    self.check_and_write(&line);
+  }
+
+  /// Warn if the line is longer than `options.max_line_length`, then
+  /// write it.
+  fn check_and_write(&mut self, line: &str) {
+    use processing_errors::ErrorKind;
+
+    self.lineno += 1;
+
+    if let Some(max_line_length) = self.options.max_line_length {
+      let width = line_width(line, self.options.tab_size);
+      if width > max_line_length {
+        self.errors.push(ErrorKind::LineTooLong(self.lineno, width).into());
+      }
+    }
+
+    self.write(line);
+  }
+
+  /// Write a line straight to the writer, without any checks.
+  fn write(&mut self, line: &str) {
+    use processing_errors::ErrorKind;
+
+    if self.failed {
+      return;
+    }
+
+    match writeln!(self.writer, "{}", line) {
+      Ok(()) => self.written += 1,
+      Err(err) => {
+        self.failed = true;
+        self.errors.push(ErrorKind::WriteFailed(err.to_string()).into());
+      }
+    };
+  }
+
+  /// Finish off the lines produced from the inputs, dropping any blank
+  /// lines held back at the end. Returns the errors found so far.
+  fn end_lines(&mut self) -> Vec<processing_errors::Error> {
+    self.held_blank.clear();
+    mem::take(&mut self.errors)
+  }
+
+  /// Flush the writer, returning the number of lines written and any
+  /// errors since `end_lines()`.
+  fn finish(mut self) -> (usize, Vec<processing_errors::Error>) {
+    use processing_errors::ErrorKind;
+
+    if !self.failed {
+      if let Err(err) = self.writer.flush() {
+        self.errors.push(ErrorKind::WriteFailed(err.to_string()).into());
+      }
+    }
+
+    (self.written, self.errors)
+  }
 }
 
 fn maybe_block_header(block: &Block, options: &OutputOptions) -> Option<String> {
+  if block.lines.len() < options.header_min_block_lines {
+    return None;
+  }
+
   match &options.comment {
     &Some(ref comment_prefix) => {
       let header = format!(
@@ -235,46 +1109,173 @@ fn maybe_block_header(block: &Block, options: &OutputOptions) -> Option<String>
   }
 }
 
-fn collect_anchor_lines(tangled: Tangled,
-                        anchors: &mut BTreeMap<String, Anchor>,
-                        lines: &mut Vec<String>,
-                        indentation: usize,
-                        options: &OutputOptions)
-{
-  use std::iter;
-
-  let indent_prefix = iter::repeat(' ').take(indentation).collect::<String>();
-   
-  for knot in tangled {
-    match knot {
-      Either::Left(block) => {
-        if let Some(comment) = maybe_block_header(&block, options) {
-          lines.push(indent_prefix.clone() + &comment);
-        }
+/// What's needed while collecting lines of output. Labels aren't used up
+/// when they're expanded, since `duplicate` anchors can expand them again.
+struct Collector<'a, W> {
+  anchors: &'a BTreeMap<String, Anchor>,
+  options: &'a OutputOptions,
+  keep_gaps: bool,
+  output: OutputWriter<'a, W>,
+  /// Whether any block has been output yet, so that block separators
+  /// only go *between* blocks.
+  emitted_block: bool,
+  /// The labels currently being expanded, innermost last.
+  expanding: Vec<String>,
+  /// What `revision` anchors expand to, looked up the first time one is
+  /// found so that git only runs once.
+  revision: Option<String>,
+  errors: Vec<processing_errors::Error>
+}
 
-        for line in block.lines {
-          lines.push(indent_prefix.clone() + &line);
+impl<'a, W: io::Write> Collector<'a, W> {
+  fn collect_anchor_lines(&mut self, tangled: &Tangled, indentation: usize) {
+    use std::iter;
+
+    let indent_prefix = iter::repeat(' ').take(indentation).collect::<String>();
+     
+    for knot in tangled.iter() {
+      match *knot {
+        Knot::Block(ref block) if block.raw => {
+          // Separated from other blocks like any block is, but the lines
+          // themselves are left alone.
+          if let Some(ref separator) = self.options.block_separator {
+            if self.emitted_block {
+              self.output.push(indent_prefix.clone() + separator);
+            }
+          }
+          self.emitted_block = true;
+
+          for line in &block.lines {
+            self.output.push(line.clone());
+          }
+        },
+        Knot::Block(ref block) => {
+          if let Some(ref separator) = self.options.block_separator {
+            if self.emitted_block {
+              self.output.push(indent_prefix.clone() + separator);
+            }
+          }
+          self.emitted_block = true;
+
+          if let Some(comment) = maybe_block_header(block, self.options) {
+            self.output.push(indent_prefix.clone() + &comment);
+          }
+
+          let split_embedded_newlines = self.options.split_embedded_newlines;
+          let strip_prefix = self.options.strip_prefix.as_ref();
+          let lines: Vec<&str> = block.lines.iter()
+            .flat_map(|line| {
+              if split_embedded_newlines {
+                line.split('\n').collect()
+              } else {
+                vec![line.as_str()]
+              }
+            })
+            .map(|line| without_prefix(line, strip_prefix))
+            .collect();
+
+          let dedent = block_dedent(&lines, self.options.block_indentation);
+          for line in lines {
+            let indented = line.len() - trim_indentation(line).len();
+            self.output.push(indent_prefix.clone() + &line[indented.min(dedent)..]);
+          }
+        },
+        Knot::Anchor(AnchorRef(ref anchor_name)) => {
+          let anchor_indentation = self.anchors.get(anchor_name)
+            .expect("invariant violated: anchor name does not exist")
+            .indentation;
+
+          self.collect_label_lines(anchor_name, indentation + anchor_indentation);
+        },
+        Knot::Duplicate(AnchorRef(ref anchor_name), duplicate_indentation) => {
+          self.collect_label_lines(anchor_name, indentation + duplicate_indentation);
+        },
+        Knot::Comment(ref comment) => {
+          let line = self.comment_line(comment.indentation, &comment.text);
+          self.output.push(indent_prefix.clone() + &line);
+        },
+        Knot::Toc(ref toc) => {
+          for entry in &toc.entries {
+            let line = self.comment_line(toc.indentation, entry);
+            self.output.push(indent_prefix.clone() + &line);
+          }
+        },
+        Knot::Stamp(ref stamp) => {
+          let text = self.stamp_text(stamp.kind);
+          let line = self.comment_line(stamp.indentation, &text);
+          self.output.push(indent_prefix.clone() + &line);
+        },
+        Knot::Gap => {
+          if self.keep_gaps {
+            self.output.push(String::new());
+          }
         }
-      },
-      Either::Right(AnchorRef(ref anchor_name)) => {
-        let anchor = anchors.remove(anchor_name)
-          .expect("invariant violated: anchor name does not exist");
-
-        collect_anchor_lines(
-          anchor.tangled,
-          anchors,
-          lines,
-          indentation + anchor.indentation,
-          options
-        );
+      };
+    }
+  }
+
+  /// A line of comment text at the given indentation, with the comment
+  /// leader in front of it if there is one.
+  fn comment_line(&self, indentation: usize, text: &str) -> String {
+    let comment_prefix = " ".repeat(indentation);
+
+    match self.options.comment {
+      Some(ref leader) => format!("{}{} {}", comment_prefix, leader, text),
+      None => comment_prefix + text
+    }
+  }
+
+  /// What a `timestamp` or `revision` anchor expands to right now. If the
+  /// revision can't be found, a placeholder is used instead, and the
+  /// problem is only reported the first time.
+  fn stamp_text(&mut self, kind: StampKind) -> String {
+    use processing_errors::ErrorKind;
+
+    match kind {
+      StampKind::Timestamp => current_timestamp(),
+      StampKind::Revision => {
+        let errors = &mut self.errors;
+        self.revision.get_or_insert_with(|| match git_revision() {
+          Ok(revision) => revision,
+          Err(reason) => {
+            errors.push(ErrorKind::UnknownRevision(reason).into());
+            "unknown".to_string()
+          }
+        }).clone()
       }
-    };
+    }
+  }
+
+  /// Expand the contents of the label at the given indentation, unless
+  /// we're already somewhere inside it.
+  fn collect_label_lines(&mut self, anchor_name: &str, indentation: usize) {
+    use processing_errors::ErrorKind;
+
+    if self.expanding.iter().any(|name| name == anchor_name) {
+      self.errors.push(ErrorKind::RecursiveLabel(anchor_name.to_string()).into());
+      return;
+    }
+
+    let anchors = self.anchors;
+    let anchor = anchors.get(anchor_name)
+      .expect("invariant violated: anchor name does not exist");
+
+    self.expanding.push(anchor_name.to_string());
+    self.collect_anchor_lines(&anchor.tangled, indentation);
+    self.expanding.pop();
   }
 }
 
 /// We scan through each file block by block.
 /// Each block will end in either an anchor tag, or the end of the file.
-fn process_block_lines<I>(lines: &mut I, block: &mut Block, errors: &mut Vec<processing_errors::Error>) -> Option<(usize, usize, parsing::Anchor)> where
+///
+/// Line numbers are reported relative to `line_base`, the line of the
+/// last `source` anchor in the file (or 0 if there wasn't one).
+fn process_block_lines<I>(lines: &mut I,
+                          line_base: usize,
+                          options: &OutputOptions,
+                          block: &mut Block,
+                          errors: &mut Vec<processing_errors::Error>) -> Option<(usize, usize, parsing::Anchor)> where
   I: Iterator<Item=(usize, result::Result<String, io::Error>)>
 {
   use processing_errors::ErrorKind;
@@ -283,12 +1284,16 @@ fn process_block_lines<I>(lines: &mut I, block: &mut Block, errors: &mut Vec<pro
   let filename = block.file.deref();
 
   for (lineno, line) in lines {
+    let lineno = lineno - line_base;
+
     match line {
       Ok(line) => {
-        let result = parsing::might_be_anchor(&line)
+        let result = options.anchor_syntaxes.iter()
+          .filter_map(|syntax| syntax.might_be_anchor(&line).map(|found| (syntax, found)))
+          .next()
           .ok_or(None)
-          .and_then(|found| {
-            parsing::parse(found.as_str())
+          .and_then(|(syntax, found)| {
+            syntax.parse(found.as_str())
               .map_err(|_| Some(ErrorKind::MalformedAnchor(
                 filename.clone(),
                 lineno,
@@ -297,7 +1302,12 @@ fn process_block_lines<I>(lines: &mut I, block: &mut Block, errors: &mut Vec<pro
           });
 
         match result {
-          Ok(anchor) => return Some((lineno, indentation_level(&line), anchor)),
+          Ok(anchor) => {
+            // The prefix is removed from block lines on output, so the
+            // anchor's indentation only counts what comes after it.
+            let indentation = indentation_level(without_prefix(&line, options.strip_prefix.as_ref()), options.tab_size);
+            return Some((lineno, indentation, anchor));
+          },
           Err(Some(error)) => {
             errors.push(error);
             block.lines.push(line);
@@ -314,13 +1324,176 @@ fn process_block_lines<I>(lines: &mut I, block: &mut Block, errors: &mut Vec<pro
   None
 }
 
-/// Index of first non-whitespace character.
-fn indentation_level(line: &str) -> usize {
-  use regex::Regex;
+/// Read the lines of a `raw` region into `block` as-is, without looking
+/// for anchors, up to the `endraw` anchor that ends it or the end of the
+/// file. Returns the line number of the `endraw` anchor, if there was one.
+fn process_raw_lines<I>(lines: &mut I,
+                        line_base: usize,
+                        options: &OutputOptions,
+                        block: &mut Block,
+                        errors: &mut Vec<processing_errors::Error>) -> Option<usize> where
+  I: Iterator<Item=(usize, result::Result<String, io::Error>)>
+{
+  use processing_errors::ErrorKind;
+
+  for (lineno, line) in lines {
+    let lineno = lineno - line_base;
+
+    match line {
+      Ok(line) => {
+        let ends_region = options.anchor_syntaxes.iter().any(|syntax| {
+          syntax.might_be_anchor(&line)
+            .and_then(|found| syntax.parse(found.as_str()).ok()) == Some(parsing::Anchor::EndRaw)
+        });
+
+        if ends_region {
+          return Some(lineno);
+        }
+        block.lines.push(line);
+      },
+      Err(_) => errors.push(ErrorKind::NotUTF8(block.file.to_string(), lineno).into())
+    };
+  }
+
+  None
+}
+
+/// Add a comment to the end of the output for each error, if
+/// `options.embed_diagnostics` is set.
+fn embed_diagnostics<W: io::Write>(output: &mut OutputWriter<W>,
+                                   errors: &[processing_errors::Error],
+                                   options: &OutputOptions)
+{
+  if !options.embed_diagnostics { return; }
+
+  if let Some(ref leader) = options.comment {
+    for error in errors {
+      output.write(&format!("{} {}", leader, error));
+    }
+  }
+}
+
+/// How many columns the whole line takes up, counting tabs the same way
+/// as `indentation_level()`.
+fn line_width(line: &str, tab_size: usize) -> usize {
+  use std::cmp;
+
+  let tab_size = cmp::max(tab_size, 1);
+
+  line.chars().fold(0, |column, c| match c {
+    '\t' => column + tab_size - column % tab_size,
+    _ => column + 1
+  })
+}
+
+/// The current time in UTC, as with `format_timestamp()`.
+fn current_timestamp() -> String {
+  use std::time::{SystemTime, UNIX_EPOCH};
+
+  let seconds = SystemTime::now().duration_since(UNIX_EPOCH)
+    .map(|elapsed| elapsed.as_secs())
+    .unwrap_or(0);
+
+  format_timestamp(seconds)
+}
+
+/// Seconds since the Unix epoch as an RFC 3339 timestamp in UTC, e.g.
+/// `2017-03-01T12:30:00Z`.
+fn format_timestamp(seconds: u64) -> String {
+  let days = (seconds / 86400) as i64;
+  let time = seconds % 86400;
+
+  // Days to a civil date, from Howard Hinnant's `civil_from_days`.
+  let shifted = days + 719_468;
+  let era = shifted / 146_097;
+  let day_of_era = shifted - era * 146_097;
+  let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let shifted_month = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+  let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+  let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+  format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+          year, month, day, time / 3600, time % 3600 / 60, time % 60)
+}
+
+/// The short hash of the `HEAD` commit of the git repository in the
+/// current directory, or why it couldn't be found.
+fn git_revision() -> result::Result<String, String> {
+  use std::process::Command;
+
+  let output = Command::new("git")
+    .args(&["rev-parse", "--short", "HEAD"][..])
+    .output()
+    .map_err(|err| format!("could not run git: {}", err))?;
+
+  if !output.status.success() {
+    let message = String::from_utf8_lossy(&output.stderr);
+    return Err(message.trim().to_string());
+  }
+
+  Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Column of first non-whitespace character, with tabs moving to the
+/// next multiple of `tab_size`.
+fn indentation_level(line: &str, tab_size: usize) -> usize {
+  use std::cmp;
+
+  let tab_size = cmp::max(tab_size, 1);
+  let mut column = 0;
+
+  for c in line.chars() {
+    match c {
+      '\t' => column += tab_size - column % tab_size,
+      c if c.is_whitespace() => column += 1,
+      _ => break
+    };
+  }
+
+  column
+}
+
+#[cfg(test)]
+mod collecting_tests {
+  use std::rc::Rc;
+  use std::collections::BTreeMap;
+
+  use list::List;
+  use super::{Anchor, AnchorRef, Block, Knot, OutputOptions, buffered_lines, format_timestamp, write_tangled_output};
+
+  #[test]
+  fn test_split_embedded_newlines() {
+    let mut block = Block::new(Rc::new("input".to_string()), 1);
+    block.lines.push("first\nsecond".to_string());
+
+    let mut anchor = Anchor::new(2);
+    anchor.tangled.push_back(Knot::Block(block));
+
+    let mut anchors = BTreeMap::new();
+    anchors.insert("Body".to_string(), anchor);
+
+    let mut tangled = List::new();
+    tangled.push_back(Knot::Anchor(AnchorRef("Body".to_string())));
+
+    let options = OutputOptions {
+      split_embedded_newlines: true,
+      ..Default::default()
+    };
+
+    let mut errors = Vec::new();
+    let mut buffer = Vec::new();
+    write_tangled_output(&tangled, &anchors, None, &options, false, &mut errors, &mut buffer);
+
+    assert_eq!(buffered_lines(buffer), vec!["  first".to_string(), "  second".to_string()]);
+  }
 
-  let nonwhitespace = Regex::new(r"[^\s]").unwrap();
-  match nonwhitespace.find(line) {
-    Some(found) => found.start(),
-    None => 0
+  #[test]
+  fn test_format_timestamp() {
+    assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+    assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    assert_eq!(format_timestamp(1_488_371_400), "2017-03-01T12:30:00Z");
+    assert_eq!(format_timestamp(4_102_444_799), "2099-12-31T23:59:59Z");
   }
 }
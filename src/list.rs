@@ -4,8 +4,13 @@
 //! The only reason this module exists is because `std::collections::LinkedList`
 //! doesn't have an `append_front()` method, for some ungodly reason.
 
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
 use std::marker::PhantomData;
 use std::mem;
+use std::ops::{Bound, Index, IndexMut, RangeBounds};
 
 use std::iter::FromIterator;
 
@@ -25,9 +30,65 @@ pub struct Iter<'a, T: 'a> {
   marker: PhantomData<&'a Node<T>>
 }
 
+pub struct IterMut<'a, T: 'a> {
+  front: Option<*mut Node<T>>,
+  back: Option<*mut Node<T>>,
+  len: usize,
+  marker: PhantomData<&'a mut Node<T>>
+}
+
 pub struct IntoIter<T> {
   list: List<T>
 }
+
+/// Groups of elements from `List::chunks_exact()`.
+pub struct ChunksExact<'a, T: 'a> {
+  iter: Iter<'a, T>,
+  size: usize,
+  left: usize  // How many elements are left in full-size groups.
+}
+
+/// Overlapping groups of elements from `List::windows()`.
+pub struct Windows<'a, T: 'a> {
+  iter: Iter<'a, T>,
+  size: usize,
+  window: VecDeque<&'a T>  // Elements of the next window we've seen so far.
+}
+
+/// A position in a `List` which can be moved back and forth, editing the
+/// list around it as it goes. The cursor is either on an element or on the
+/// "ghost" position past the end of the list, which sits between the back
+/// and the front. Works like `std::collections::linked_list::CursorMut`.
+pub struct CursorMut<'a, T: 'a> {
+  current: Option<*mut Node<T>>,
+  list: &'a mut List<T>
+}
+
+/// The elements removed by `List::drain()`. Any that aren't iterated
+/// over are dropped along with the `Drain`.
+pub struct Drain<T> {
+  list: List<T>
+}
+
+/// The elements removed by `List::drain_filter()`. Any matching elements
+/// that aren't iterated over are still removed when it's dropped.
+pub struct DrainFilter<'a, T: 'a, F> where
+  F: FnMut(&T) -> bool
+{
+  list: &'a mut List<T>,
+  next: Option<*mut Node<T>>,  // The next node to check.
+  pred: F
+}
+
+/// The error from `List::try_get()` when the index is past the end of
+/// the list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexError {
+  /// The index that was asked for.
+  pub index: usize,
+  /// The length of the list at the time.
+  pub len: usize
+}
  
 struct Node<T> {
   to_f: Option<*mut Node<T>>,
@@ -83,6 +144,43 @@ impl<T> List<T> {
     self.len == 0
   }
 
+  /// Check if the list has at least `n` elements.
+  /// Runs in O(1) space and O(1) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// assert!(dl.has_at_least(3));
+  /// assert!(!dl.has_at_least(4));
+  /// ```
+  pub fn has_at_least(&self, n: usize) -> bool {
+    self.len >= n
+  }
+
+  /// Check if the list has exactly one element.
+  /// Runs in O(1) space and O(1) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl = List::new();
+  ///
+  /// dl.push_back(4);
+  /// assert!(dl.is_singleton());
+  ///
+  /// dl.push_back(5);
+  /// assert!(!dl.is_singleton());
+  /// ```
+  pub fn is_singleton(&self) -> bool {
+    self.len == 1
+  }
+
   /// Place `element` *before* all elements in the list.
   ///
   /// Runs in O(1) space and O(1) time.
@@ -223,6 +321,18 @@ impl<T> List<T> {
     }
   }
 
+  /// Remove both the first and the last element in the list and return them.
+  /// If the list only has a single element, it gets returned as the *first*
+  /// element, and the last is `None`.
+  ///
+  /// Runs in O(1) space and O(1) time.
+  pub fn pop_ends(&mut self) -> (Option<T>, Option<T>) {
+    let front = self.pop_front();
+    let back = self.pop_back();
+
+    (front, back)
+  }
+
   /// Place all elements in `other` *before* all elements in the list.
   /// Reuses the nodes in `other`, placing them into the list. After the operation,
   /// `other` becomes empty.
@@ -315,142 +425,2409 @@ impl<T> List<T> {
     };
   }
 
-  pub fn iter(&self) -> Iter<T> {
-    Iter { 
-      front: self.front,
-      back: self.back,
-      len: self.len,
-      marker: PhantomData
+  /// Clone each element of `items` onto the front of the list, keeping
+  /// them in the same order as in the slice.
+  ///
+  /// Runs in O(m) space and O(m) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<i32> = vec![1, 2].into_iter().collect();
+  ///
+  /// dl.push_all_front(&[0, -1]);
+  ///
+  /// assert_eq!(format!("{:?}", dl), "List[0, -1, 1, 2]");
+  /// ```
+  pub fn push_all_front(&mut self, items: &[T]) where
+    T: Clone
+  {
+    for item in items.iter().rev() {
+      self.push_front(item.clone());
     }
   }
-}
-
-impl<T> Drop for List<T> {
-  fn drop(&mut self) {
-    let mut here = self.front;
 
-    while let Some(node) = here {
-      unsafe {
-        let node = Box::from_raw(node);
-        here = node.to_b;
-      }
+  /// Clone each element of `items` onto the back of the list, keeping
+  /// them in the same order as in the slice.
+  ///
+  /// Runs in O(m) space and O(m) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<i32> = vec![1, 2].into_iter().collect();
+  ///
+  /// dl.push_all_back(&[3, 4]);
+  ///
+  /// assert_eq!(format!("{:?}", dl), "List[1, 2, 3, 4]");
+  /// ```
+  pub fn push_all_back(&mut self, items: &[T]) where
+    T: Clone
+  {
+    for item in items {
+      self.push_back(item.clone());
     }
   }
-}
 
-impl<T> IntoIterator for List<T> {
-  type Item = T;
-  type IntoIter = IntoIter<T>;
+  /// Put all the items from `iter` at the front of the list, keeping them
+  /// in the order they came in, so that the first item becomes the front.
+  ///
+  /// Runs in O(m) space and O(m) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![3, 4].into_iter().collect();
+  ///
+  /// dl.extend_front(vec![1, 2]);
+  ///
+  /// assert_eq!(format!("{:?}", dl), "List[1, 2, 3, 4]");
+  /// ```
+  pub fn extend_front<I>(&mut self, iter: I) where
+    I: IntoIterator<Item=T>
+  {
+    let mut items: List<T> = iter.into_iter().collect();
+    self.append_front(&mut items);
+  }
 
-  fn into_iter(self) -> IntoIter<T> {
-    IntoIter {
-      list: self
+  /// Put all the items from `iter` at the back of the list, in the order
+  /// they came in.
+  ///
+  /// Runs in O(m) space and O(m) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2].into_iter().collect();
+  ///
+  /// dl.extend_back(vec![3, 4]);
+  ///
+  /// assert_eq!(format!("{:?}", dl), "List[1, 2, 3, 4]");
+  /// ```
+  pub fn extend_back<I>(&mut self, iter: I) where
+    I: IntoIterator<Item=T>
+  {
+    for item in iter {
+      self.push_back(item);
     }
   }
-}
 
-impl<'a, T> Iterator for Iter<'a, T> {
-  type Item = &'a T;
+  /// Move all elements of `other` into the list, directly before the
+  /// element at index `at`, leaving `other` empty. If `at` is the length of
+  /// the list, they go at the back. Reuses the nodes in `other`.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `at` is greater than the length of the list.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 4].into_iter().collect();
+  /// let mut other: List<u32> = vec![2, 3].into_iter().collect();
+  ///
+  /// dl.splice_before(1, &mut other);
+  ///
+  /// assert_eq!(format!("{:?}", dl), "List[1, 2, 3, 4]");
+  /// assert!(other.is_empty());
+  /// ```
+  pub fn splice_before(&mut self, at: usize, other: &mut List<T>) {
+    assert!(at <= self.len, "splice index {} is out of bounds for length {}", at, self.len);
 
-  fn next(&mut self) -> Option<&'a T> {
     unsafe {
-      if self.len == 0 { return None; }
-
-      let node = self.front
-        .expect("invariant violated: front is None");
-
-      self.len -= 1;
-      self.front = (*node).to_b;
-
-      Some(&(*node).data)
+      let next = self.node_at(at);
+      let prev = match next {
+        Some(next) => (*next).to_f,
+        None => self.back
+      };
+      self.link_between(prev, next, other);
     }
   }
-}
 
-impl<'a, T> ExactSizeIterator for Iter<'a, T> {
-  fn len(&self) -> usize {
-    self.len
-  }
-}
+  /// Move all elements of `other` into the list, directly after the
+  /// element at index `at`, leaving `other` empty. Reuses the nodes in
+  /// `other`.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Panics
+  ///
+  /// Panics if there is no element at index `at`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 4].into_iter().collect();
+  /// let mut other: List<u32> = vec![2, 3].into_iter().collect();
+  ///
+  /// dl.splice_after(0, &mut other);
+  ///
+  /// assert_eq!(format!("{:?}", dl), "List[1, 2, 3, 4]");
+  /// assert!(other.is_empty());
+  /// ```
+  pub fn splice_after(&mut self, at: usize, other: &mut List<T>) {
+    assert!(at < self.len, "splice index {} is out of bounds for length {}", at, self.len);
 
-impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
-  fn next_back(&mut self) -> Option<&'a T> {
     unsafe {
-      if self.len == 0 { return None; }
+      let prev = self.node_at(at);
+      let next = prev.and_then(|prev| (*prev).to_b);
+      self.link_between(prev, next, other);
+    }
+  }
 
-      let node = self.back
-        .expect("invariant violated: back is None");
+  /// Insert `element` at the position which keeps an already-sorted list
+  /// sorted. If there are elements equal to `element` in the list, it gets
+  /// placed *after* all of them.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  /// use std::iter::IntoIterator;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 4, 5].into_iter().collect();
+  ///
+  /// dl.insert_sorted(3);
+  ///
+  /// let numbers: [u32; 5] = [1, 2, 3, 4, 5];
+  /// let collected: Vec<u32> = dl.into_iter().collect();
+  /// assert_eq!(&numbers as &[u32], &collected as &[u32]);
+  /// ```
+  pub fn insert_sorted(&mut self, element: T) where
+    T: Ord
+  {
+    let mut here = self.front;
 
-      self.len -= 1;
-      self.back = (*node).to_f;
+    unsafe {
+      while let Some(node) = here {
+        if (*node).data > element { break; }
+        here = (*node).to_b;
+      }
 
-      Some(&(*node).data)
+      self.insert_before_node(here, element);
     }
   }
-}
 
-impl<T> Iterator for IntoIter<T> {
-  type Item = T;
+  /// Check that `pred` holds for every pair of adjacent elements in the list,
+  /// e.g. `|a, b| a <= b` to check that the list is sorted in ascending order.
+  /// Empty and single-element lists are always sorted.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let sorted: List<u32> = vec![1, 2, 3].into_iter().collect();
+  /// let unsorted: List<u32> = vec![3, 1, 2].into_iter().collect();
+  ///
+  /// assert!(sorted.is_sorted_by(|a, b| a <= b));
+  /// assert!(!unsorted.is_sorted_by(|a, b| a <= b));
+  /// ```
+  pub fn is_sorted_by<F>(&self, mut pred: F) -> bool where
+    F: FnMut(&T, &T) -> bool
+  {
+    let mut elements = self.iter();
+    let mut prev = match elements.next() {
+      None => return true,
+      Some(element) => element
+    };
 
-  fn next(&mut self) -> Option<T> {
-    self.list.pop_front()
-  }
-}
+    for element in elements {
+      if !pred(prev, element) { return false; }
+      prev = element;
+    }
 
-impl<T> ExactSizeIterator for IntoIter<T> {
-  fn len(&self) -> usize {
-    self.list.len
+    true
   }
-}
 
-impl<T> DoubleEndedIterator for IntoIter<T> {
-  fn next_back(&mut self) -> Option<T> {
-    self.list.pop_back()
+  /// Consume the list, yielding its elements from back to front.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  /// let drained: Vec<u32> = dl.into_iter_rev().collect();
+  ///
+  /// assert_eq!(drained, vec![3, 2, 1]);
+  /// ```
+  pub fn into_iter_rev(self) -> impl Iterator<Item=T> {
+    self.into_iter().rev()
   }
-}
 
-impl<A> FromIterator<A> for List<A>
-{
-  /// # Examples
+  /// Merge the elements of `other` into the list, assuming that both are
+  /// already sorted, so that the result is sorted as well. Elements of the
+  /// list come before equal elements of `other`. Reuses the nodes in `other`.
   ///
-  /// Using it directly:
+  /// Runs in O(1) space and O(n + m) time.
+  ///
+  /// # Examples
   ///
   /// ```
   /// use kaiseki::list::List;
-  /// use std::iter::{IntoIterator, FromIterator};
   ///
-  /// let numbers: Vec<u32> = vec![1, 2, 3, 4];
-  /// let list = List::from_iter(numbers);
+  /// let mut dl1: List<u32> = vec![1, 3, 5].into_iter().collect();
+  /// let dl2: List<u32> = vec![2, 4].into_iter().collect();
   ///
-  /// assert_eq!(list.len(), 4);
+  /// dl1.merge_run(dl2);
   ///
-  /// let mut iter = list.into_iter();
-  /// assert_eq!(iter.next(), Some(1));
-  /// assert_eq!(iter.next(), Some(2));
-  /// assert_eq!(iter.next(), Some(3));
-  /// assert_eq!(iter.next(), Some(4));
-  /// assert_eq!(iter.next(), None);
+  /// let collected: Vec<u32> = dl1.into_iter().collect();
+  /// assert_eq!(collected, vec![1, 2, 3, 4, 5]);
   /// ```
+  pub fn merge_run(&mut self, other: List<T>) where
+    T: Ord
+  {
+    self.merge_by(other, &T::cmp);
+  }
+
+  /// Sort the list in place, keeping equal elements in the order they were
+  /// in. Nodes are relinked rather than having their contents moved.
   ///
-  /// Through `collect()`:
+  /// Runs in O(log n) space and O(n log n) time.
+  ///
+  /// # Examples
   ///
   /// ```
   /// use kaiseki::list::List;
-  /// use std::iter::IntoIterator;
   ///
-  /// let numbers: Vec<u32> = vec![1, 2, 3, 4];
-  /// let list: List<u32> = numbers.into_iter().collect();
+  /// let mut dl: List<u32> = vec![3, 1, 2].into_iter().collect();
   ///
-  /// assert_eq!(list.len(), 4);
+  /// dl.sort_by(|a, b| b.cmp(a));
   ///
-  /// let mut iter = list.into_iter();
-  /// assert_eq!(iter.next(), Some(1));
-  /// assert_eq!(iter.next(), Some(2));
-  /// assert_eq!(iter.next(), Some(3));
-  /// assert_eq!(iter.next(), Some(4));
-  /// assert_eq!(iter.next(), None);
+  /// assert_eq!(dl.iter().collect::<Vec<&u32>>(), vec![&3, &2, &1]);
   /// ```
-  fn from_iter<I>(iter: I) -> Self where
+  pub fn sort_by<F>(&mut self, compare: F) where
+    F: Fn(&T, &T) -> Ordering
+  {
+    self.merge_sort(&compare);
+  }
+
+  /// Sort the list in place, keeping equal elements in the order they were
+  /// in.
+  ///
+  /// Runs in O(log n) space and O(n log n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![3, 1, 2].into_iter().collect();
+  ///
+  /// dl.sort();
+  ///
+  /// assert_eq!(dl.iter().collect::<Vec<&u32>>(), vec![&1, &2, &3]);
+  /// ```
+  pub fn sort(&mut self) where
+    T: Ord
+  {
+    self.sort_by(T::cmp);
+  }
+
+  /// Get a reference to the smallest element, or `None` if the list is
+  /// empty. If several elements are equally small, the first is returned.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![3, 1, 2].into_iter().collect();
+  /// let empty: List<u32> = List::new();
+  ///
+  /// assert_eq!(dl.min(), Some(&1));
+  /// assert_eq!(empty.min(), None);
+  /// ```
+  pub fn min(&self) -> Option<&T> where
+    T: Ord
+  {
+    self.iter().min()
+  }
+
+  /// Get a reference to the largest element, or `None` if the list is
+  /// empty. If several elements are equally large, the last is returned.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![3, 1, 2].into_iter().collect();
+  /// let empty: List<u32> = List::new();
+  ///
+  /// assert_eq!(dl.max(), Some(&3));
+  /// assert_eq!(empty.max(), None);
+  /// ```
+  pub fn max(&self) -> Option<&T> where
+    T: Ord
+  {
+    self.iter().max()
+  }
+
+  /// Keep only the elements for which `f` returns `true`, removing the
+  /// rest in place. Elements are visited front to back.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3, 4, 5].into_iter().collect();
+  ///
+  /// dl.retain(|n| n % 2 == 1);
+  ///
+  /// assert_eq!(dl.iter().collect::<Vec<&u32>>(), vec![&1, &3, &5]);
+  /// ```
+  pub fn retain<F>(&mut self, mut f: F) where
+    F: FnMut(&T) -> bool
+  {
+    let mut here = self.front;
+
+    unsafe {
+      while let Some(node) = here {
+        here = (*node).to_b;
+
+        if !f(&(*node).data) {
+          self.unlink_node(node);
+        }
+      }
+    }
+  }
+
+  /// Remove each element for which `same_bucket` returns `true` when given
+  /// it and the element before it that was kept, so that runs of elements
+  /// in the same bucket shrink to their first element.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<i32> = vec![1, -1, 2, 3, -3, -3].into_iter().collect();
+  ///
+  /// dl.dedup_by(|a, b| a.abs() == b.abs());
+  ///
+  /// assert_eq!(format!("{:?}", dl), "List[1, 2, 3]");
+  /// ```
+  pub fn dedup_by<F>(&mut self, mut same_bucket: F) where
+    F: FnMut(&T, &T) -> bool
+  {
+    let mut kept = match self.front {
+      None => return,
+      Some(front) => front
+    };
+
+    unsafe {
+      let mut here = (*kept).to_b;
+      while let Some(node) = here {
+        here = (*node).to_b;
+
+        if same_bucket(&(*node).data, &(*kept).data) {
+          self.unlink_node(node);
+        } else {
+          kept = node;
+        }
+      }
+    }
+  }
+
+  /// Keep only the *first* element for each distinct key, removing any
+  /// later elements with the same key, wherever they are in the list.
+  ///
+  /// Runs in O(k) space and O(nk) time, where `k` is the number of
+  /// distinct keys.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<&str> = vec!["a", "b", "a", "c", "b"].into_iter().collect();
+  ///
+  /// dl.retain_first(|s| *s);
+  ///
+  /// let collected: Vec<&str> = dl.into_iter().collect();
+  /// assert_eq!(collected, vec!["a", "b", "c"]);
+  /// ```
+  pub fn retain_first<K, F>(&mut self, mut key: F) where
+    K: PartialEq,
+    F: FnMut(&T) -> K
+  {
+    let mut seen = Vec::new();
+    let mut here = self.front;
+
+    unsafe {
+      while let Some(node) = here {
+        here = (*node).to_b;
+
+        let node_key = key(&(*node).data);
+        if seen.contains(&node_key) {
+          self.unlink_node(node);
+        } else {
+          seen.push(node_key);
+        }
+      }
+    }
+  }
+
+  /// Keep only the *last* element for each distinct key, removing any
+  /// earlier elements with the same key, wherever they are in the list.
+  ///
+  /// Runs in O(k) space and O(nk) time, where `k` is the number of
+  /// distinct keys.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<&str> = vec!["a", "b", "a", "c", "b"].into_iter().collect();
+  ///
+  /// dl.retain_last(|s| *s);
+  ///
+  /// let collected: Vec<&str> = dl.into_iter().collect();
+  /// assert_eq!(collected, vec!["a", "c", "b"]);
+  /// ```
+  pub fn retain_last<K, F>(&mut self, mut key: F) where
+    K: PartialEq,
+    F: FnMut(&T) -> K
+  {
+    let mut seen = Vec::new();
+    let mut here = self.back;
+
+    unsafe {
+      while let Some(node) = here {
+        here = (*node).to_f;
+
+        let node_key = key(&(*node).data);
+        if seen.contains(&node_key) {
+          self.unlink_node(node);
+        } else {
+          seen.push(node_key);
+        }
+      }
+    }
+  }
+
+  /// Make a new list with the same elements in reverse order, leaving the
+  /// original list untouched.
+  ///
+  /// Runs in O(n) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  /// let reversed = dl.reversed();
+  ///
+  /// assert_eq!(reversed.iter().collect::<Vec<&u32>>(), vec![&3, &2, &1]);
+  /// assert_eq!(dl.iter().collect::<Vec<&u32>>(), vec![&1, &2, &3]);
+  /// ```
+  pub fn reversed(&self) -> List<T> where
+    T: Clone
+  {
+    let mut reversed = List::new();
+
+    for element in self.iter() {
+      reversed.push_front(element.clone());
+    }
+
+    reversed
+  }
+
+  /// Count the maximal runs of adjacent elements for which `same` holds
+  /// between each element and the next.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let empty: List<u32> = List::new();
+  /// let dl: List<u32> = vec![1, 1, 2, 3, 3].into_iter().collect();
+  ///
+  /// assert_eq!(empty.count_runs(|a, b| a == b), 0);
+  /// assert_eq!(dl.count_runs(|a, b| a == b), 3);
+  /// ```
+  pub fn count_runs<F>(&self, mut same: F) -> usize where
+    F: FnMut(&T, &T) -> bool
+  {
+    if self.is_empty() {
+      return 0;
+    }
+
+    1 + self.iter_pairs()
+      .filter(|&(prev, next)| !same(prev, next))
+      .count()
+  }
+
+  /// Move the leading run of elements satisfying `pred` to the back of the
+  /// list, keeping their order. Reuses the existing nodes.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![2, 4, 5, 6].into_iter().collect();
+  ///
+  /// dl.rotate_while(|n| n % 2 == 0);
+  ///
+  /// let collected: Vec<u32> = dl.into_iter().collect();
+  /// assert_eq!(collected, vec![5, 6, 2, 4]);
+  /// ```
+  pub fn rotate_while<F>(&mut self, mut pred: F) where
+    F: FnMut(&T) -> bool
+  {
+    unsafe {
+      let mut boundary = self.front;
+
+      while let Some(node) = boundary {
+        if !pred(&(*node).data) { break; }
+        boundary = (*node).to_b;
+      }
+
+      // Either nothing matched, or everything did; both leave the order as-is.
+      let boundary = match boundary {
+        Some(node) if (*node).to_f.is_some() => node,
+        _ => return
+      };
+
+      let run_front = self.front.expect("invariant violated: front is None");
+      let run_back = (*boundary).to_f.expect("invariant violated: to_f is None");
+      let back = self.back.expect("invariant violated: back is None");
+
+      (*boundary).to_f = None;
+      (*run_back).to_b = None;
+      (*run_front).to_f = Some(back);
+      (*back).to_b = Some(run_front);
+
+      self.front = Some(boundary);
+      self.back = Some(run_back);
+    }
+  }
+
+  /// Split the list at the first element satisfying `pred`, returning that
+  /// element and everything after it as a new list, and leaving everything
+  /// before it in place. Returns `None` if no element matches. Reuses the
+  /// existing nodes.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3, 4].into_iter().collect();
+  /// let tail = dl.split_at_first(|n| *n > 2).unwrap();
+  ///
+  /// assert_eq!(dl.iter().collect::<Vec<&u32>>(), vec![&1, &2]);
+  /// assert_eq!(tail.iter().collect::<Vec<&u32>>(), vec![&3, &4]);
+  /// ```
+  pub fn split_at_first<F>(&mut self, mut pred: F) -> Option<List<T>> where
+    F: FnMut(&T) -> bool
+  {
+    let mut here = self.front;
+    let mut prefix_len = 0;
+
+    unsafe {
+      while let Some(node) = here {
+        if pred(&(*node).data) { break; }
+        here = (*node).to_b;
+        prefix_len += 1;
+      }
+
+      let split = here?;
+
+      let mut tail = List::new();
+      tail.front = Some(split);
+      tail.back = self.back;
+      tail.len = self.len - prefix_len;
+
+      match (*split).to_f {
+        None => self.front = None,
+        Some(prev) => (*prev).to_b = None
+      };
+
+      self.back = (*split).to_f;
+      self.len = prefix_len;
+      (*split).to_f = None;
+
+      Some(tail)
+    }
+  }
+
+  /// Build a list from an iterator of `Result`s, stopping at the first
+  /// error and returning it. Anything built up to that point is freed.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let results: Vec<Result<u32, &str>> = vec![Ok(1), Ok(2), Ok(3)];
+  /// let dl = List::try_from_iter(results).unwrap();
+  ///
+  /// assert_eq!(dl.iter().collect::<Vec<&u32>>(), vec![&1, &2, &3]);
+  /// ```
+  pub fn try_from_iter<E, I>(iter: I) -> Result<List<T>, E> where
+    I: IntoIterator<Item=Result<T, E>>
+  {
+    let mut list = List::new();
+
+    for element in iter {
+      list.push_back(element?);
+    }
+
+    Ok(list)
+  }
+
+  /// Walk the list front to back, passing an accumulator along with each
+  /// element, which `f` may modify. Returns the final accumulator.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// let total = dl.fold_mut(0, |sum, n| {
+  ///   *n += sum;
+  ///   *n
+  /// });
+  ///
+  /// assert_eq!(total, 6);
+  /// assert_eq!(dl.iter().collect::<Vec<&u32>>(), vec![&1, &3, &6]);
+  /// ```
+  pub fn fold_mut<B, F>(&mut self, init: B, mut f: F) -> B where
+    F: FnMut(B, &mut T) -> B
+  {
+    let mut acc = init;
+    let mut here = self.front;
+
+    unsafe {
+      while let Some(node) = here {
+        acc = f(acc, &mut (*node).data);
+        here = (*node).to_b;
+      }
+    }
+
+    acc
+  }
+
+  /// Combine the elements of the list front to back, as with `fold`, but
+  /// stop at the first error `f` returns.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let sum_positive = |sum: i32, &n: &i32| {
+  ///   if n < 0 { Err(n) } else { Ok(sum + n) }
+  /// };
+  ///
+  /// let dl: List<i32> = vec![1, 2, 3].into_iter().collect();
+  /// assert_eq!(dl.try_fold(0, sum_positive), Ok(6));
+  ///
+  /// let dl: List<i32> = vec![1, -2, 3].into_iter().collect();
+  /// assert_eq!(dl.try_fold(0, sum_positive), Err(-2));
+  /// ```
+  pub fn try_fold<B, E, F>(&self, init: B, mut f: F) -> Result<B, E> where
+    F: FnMut(B, &T) -> Result<B, E>
+  {
+    let mut acc = init;
+
+    for element in self.iter() {
+      acc = f(acc, element)?;
+    }
+
+    Ok(acc)
+  }
+
+  /// Drop all elements of the list, leaving it empty and ready to be used
+  /// again.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// dl.clear();
+  /// assert!(dl.is_empty());
+  ///
+  /// dl.push_back(4);
+  /// assert_eq!(format!("{:?}", dl), "List[4]");
+  /// ```
+  ///
+  /// References into the list can't outlive clearing it:
+  ///
+  /// ```compile_fail
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  /// let first = dl.front();
+  ///
+  /// dl.clear();
+  /// assert_eq!(first, Some(&1));
+  /// ```
+  pub fn clear(&mut self) {
+    let mut here = self.front.take();
+    self.back = None;
+    self.len = 0;
+
+    while let Some(node) = here {
+      unsafe {
+        let node = Box::from_raw(node);
+        here = node.to_b;
+      }
+    }
+  }
+
+  /// Move all elements of the list into a `Vec`, front to back, leaving
+  /// the list empty and ready to be used again.
+  ///
+  /// Runs in O(n) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// assert_eq!(dl.clear_and_collect(), vec![1, 2, 3]);
+  /// assert!(dl.is_empty());
+  ///
+  /// dl.push_back(4);
+  /// assert_eq!(dl.clear_and_collect(), vec![4]);
+  /// ```
+  pub fn clear_and_collect(&mut self) -> Vec<T> {
+    mem::take(self).into_iter().collect()
+  }
+
+  /// Remove up to the last `n` elements of the list and return them as a
+  /// new list, leaving the earlier elements in place. Reuses the existing
+  /// nodes.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3, 4].into_iter().collect();
+  /// let taken = dl.take_back(2);
+  ///
+  /// assert_eq!(taken.iter().collect::<Vec<&u32>>(), vec![&3, &4]);
+  /// assert_eq!(dl.iter().collect::<Vec<&u32>>(), vec![&1, &2]);
+  ///
+  /// let mut dl: List<u32> = vec![1, 2].into_iter().collect();
+  /// let taken = dl.take_back(10);
+  ///
+  /// assert_eq!(taken.iter().collect::<Vec<&u32>>(), vec![&1, &2]);
+  /// assert!(dl.is_empty());
+  /// ```
+  pub fn take_back(&mut self, n: usize) -> List<T> {
+    if n >= self.len {
+      return mem::take(self);
+    }
+
+    let mut taken = List::new();
+    if n == 0 {
+      return taken;
+    }
+
+    unsafe {
+      let mut split = self.back.expect("invariant violated: back is None");
+      for _ in 1..n {
+        split = (*split).to_f.expect("invariant violated: to_f is None");
+      }
+
+      let prev = (*split).to_f.expect("invariant violated: to_f is None");
+      (*prev).to_b = None;
+      (*split).to_f = None;
+
+      taken.front = Some(split);
+      taken.back = self.back;
+      taken.len = n;
+
+      self.back = Some(prev);
+      self.len -= n;
+    }
+
+    taken
+  }
+
+  /// Check if any element of the list satisfies `f`, stopping at the
+  /// first one that does.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<i32> = vec![1, 3, 4].into_iter().collect();
+  ///
+  /// assert!(dl.any(|n| n % 2 == 0));
+  /// ```
+  pub fn any<F>(&self, f: F) -> bool where
+    F: FnMut(&T) -> bool
+  {
+    self.iter().any(f)
+  }
+
+  /// Check if every element of the list satisfies `f`, stopping at the
+  /// first one that doesn't.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<i32> = vec![1, 2, -3].into_iter().collect();
+  ///
+  /// assert!(!dl.all(|n| *n > 0));
+  /// ```
+  pub fn all<F>(&self, f: F) -> bool where
+    F: FnMut(&T) -> bool
+  {
+    self.iter().all(f)
+  }
+
+  /// Find the index of the first element satisfying `pred`.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 4, 6].into_iter().collect();
+  ///
+  /// assert_eq!(dl.position(|n| n % 2 == 0), Some(1));
+  /// assert_eq!(dl.position(|n| *n > 10), None);
+  /// ```
+  pub fn position<F>(&self, pred: F) -> Option<usize> where
+    F: FnMut(&T) -> bool
+  {
+    self.iter().position(pred)
+  }
+
+  /// Call `f` on each group of `size` consecutive elements of the list,
+  /// front to back, letting it modify them. The last group may be smaller.
+  ///
+  /// Runs in O(size) space and O(n) time.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `size` is 0.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3, 4, 5].into_iter().collect();
+  ///
+  /// dl.each_chunk_mut(2, |chunk| {
+  ///   for n in chunk.iter_mut() {
+  ///     **n *= 2;
+  ///   }
+  /// });
+  ///
+  /// assert_eq!(dl.iter().collect::<Vec<&u32>>(), vec![&2, &4, &6, &8, &10]);
+  /// ```
+  pub fn each_chunk_mut<F>(&mut self, size: usize, mut f: F) where
+    F: FnMut(&mut [&mut T])
+  {
+    assert!(size != 0, "chunk size must be nonzero");
+
+    let mut chunk = Vec::with_capacity(size);
+    let mut here = self.front;
+
+    unsafe {
+      while let Some(node) = here {
+        chunk.push(&mut (*node).data);
+        here = (*node).to_b;
+
+        if chunk.len() == size {
+          f(&mut chunk);
+          chunk.clear();
+        }
+      }
+    }
+
+    if !chunk.is_empty() {
+      f(&mut chunk);
+    }
+  }
+
+  /// Find the index where the final run of elements satisfying `pred`
+  /// starts, or `None` if the last element doesn't satisfy it.
+  ///
+  /// Runs in O(1) space and O(n) time, only looking at the final run.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 0, 0].into_iter().collect();
+  ///
+  /// assert_eq!(dl.last_run_start(|n| *n == 0), Some(2));
+  /// assert_eq!(dl.last_run_start(|n| *n == 1), None);
+  /// ```
+  pub fn last_run_start<F>(&self, mut pred: F) -> Option<usize> where
+    F: FnMut(&T) -> bool
+  {
+    let run_len = self.iter().rev().take_while(|element| pred(element)).count();
+
+    if run_len == 0 {
+      None
+    } else {
+      Some(self.len - run_len)
+    }
+  }
+
+  /// Make a new list with one element for each element of this list, as
+  /// computed by `f`, which can update `state` as it goes. The list
+  /// itself is left untouched.
+  ///
+  /// Runs in O(n) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// let sums = dl.scan(0, |sum, n| {
+  ///   *sum += *n;
+  ///   *sum
+  /// });
+  ///
+  /// assert_eq!(sums.iter().collect::<Vec<&u32>>(), vec![&1, &3, &6]);
+  /// assert_eq!(dl.len(), 3);
+  /// ```
+  pub fn scan<St, U, F>(&self, init: St, mut f: F) -> List<U> where
+    F: FnMut(&mut St, &T) -> U
+  {
+    let mut state = init;
+
+    self.iter()
+      .map(|element| f(&mut state, element))
+      .collect()
+  }
+
+  /// Combine two lists by taking elements from each in turn, starting
+  /// with this one. Once either list runs out, the rest of the other is
+  /// added at the back. Reuses the nodes of both lists.
+  ///
+  /// Runs in O(1) space and O(n + m) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl1: List<u32> = vec![1, 3, 5].into_iter().collect();
+  /// let dl2: List<u32> = vec![2, 4].into_iter().collect();
+  ///
+  /// let interleaved = dl1.interleave(dl2);
+  ///
+  /// let collected: Vec<u32> = interleaved.into_iter().collect();
+  /// assert_eq!(collected, vec![1, 2, 3, 4, 5]);
+  /// ```
+  pub fn interleave(mut self, mut other: List<T>) -> List<T> {
+    let mut interleaved = List::new();
+
+    unsafe {
+      while self.front.is_some() && other.front.is_some() {
+        let ours = self.pop_front_node().expect("invariant violated: front is None");
+        let theirs = other.pop_front_node().expect("invariant violated: front is None");
+
+        interleaved.push_back_node(ours);
+        interleaved.push_back_node(theirs);
+      }
+    }
+
+    interleaved.append_back(&mut self);
+    interleaved.append_back(&mut other);
+    interleaved
+  }
+
+  /// Remove the elements in the given range of indices from the list,
+  /// yielding them front to back. The rest of the list is joined back up
+  /// straight away, even if the `Drain` is never used.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the start of the range is after the end, or the end is
+  /// past the end of the list.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3, 4, 5].into_iter().collect();
+  /// let drained: Vec<u32> = dl.drain(1..3).collect();
+  ///
+  /// assert_eq!(drained, vec![2, 3]);
+  /// assert_eq!(dl.iter().collect::<Vec<&u32>>(), vec![&1, &4, &5]);
+  /// ```
+  pub fn drain<R>(&mut self, range: R) -> Drain<T> where
+    R: RangeBounds<usize>
+  {
+    let start = match range.start_bound() {
+      Bound::Included(&n) => n,
+      Bound::Excluded(&n) => n + 1,
+      Bound::Unbounded => 0
+    };
+    let end = match range.end_bound() {
+      Bound::Included(&n) => n + 1,
+      Bound::Excluded(&n) => n,
+      Bound::Unbounded => self.len
+    };
+
+    assert!(start <= end, "drain start {} is after end {}", start, end);
+    assert!(end <= self.len, "drain end {} is out of bounds for length {}", end, self.len);
+
+    let mut drained = self.split_off(start);
+    let mut rest = drained.split_off(end - start);
+    self.append_back(&mut rest);
+
+    Drain { list: drained }
+  }
+
+  /// Remove the elements for which `pred` returns `true`, yielding them
+  /// front to back. Elements are only checked as the iterator is advanced,
+  /// but the list is left the same whether or not it's used up: dropping
+  /// the `DrainFilter` removes any remaining matches.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3, 4].into_iter().collect();
+  /// let evens: Vec<u32> = dl.drain_filter(|n| n % 2 == 0).collect();
+  ///
+  /// assert_eq!(evens, vec![2, 4]);
+  /// assert_eq!(format!("{:?}", dl), "List[1, 3]");
+  /// ```
+  pub fn drain_filter<F>(&mut self, pred: F) -> DrainFilter<T, F> where
+    F: FnMut(&T) -> bool
+  {
+    DrainFilter { next: self.front, list: self, pred }
+  }
+
+  /// Remove and drop the elements with indices from `start` up to, but not
+  /// including, `end`.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `start` is after `end`, or `end` is past the end of the
+  /// list.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<&str> = vec!["a", "b", "c", "d"].into_iter().collect();
+  ///
+  /// dl.remove_range(1, 3);
+  ///
+  /// assert_eq!(format!("{:?}", dl), r#"List["a", "d"]"#);
+  /// ```
+  pub fn remove_range(&mut self, start: usize, end: usize) {
+    self.drain(start..end);
+  }
+
+  /// Move all elements of the list onto the end of `out`, front to back.
+  /// Useful for accumulating several lists into one buffer without
+  /// allocating a new `Vec` for each.
+  ///
+  /// Runs in O(1) space and O(n) time, not counting any growth of `out`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![3, 4].into_iter().collect();
+  /// let mut out = vec![1, 2];
+  ///
+  /// dl.collect_into(&mut out);
+  /// assert_eq!(out, vec![1, 2, 3, 4]);
+  /// ```
+  pub fn collect_into(self, out: &mut Vec<T>) {
+    out.reserve(self.len());
+    out.extend(self);
+  }
+
+  /// Split the list into the first `at` elements, which stay, and the
+  /// rest, which are returned as a new list. Reuses the existing nodes.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `at` is greater than the length of the list.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3, 4].into_iter().collect();
+  /// let tail = dl.split_off(1);
+  ///
+  /// assert_eq!(dl.iter().collect::<Vec<&u32>>(), vec![&1]);
+  /// assert_eq!(tail.iter().collect::<Vec<&u32>>(), vec![&2, &3, &4]);
+  /// ```
+  #[must_use]
+  pub fn split_off(&mut self, at: usize) -> List<T> {
+    assert!(at <= self.len, "split index {} is out of bounds for length {}", at, self.len);
+
+    if at == self.len {
+      return List::new();
+    }
+    if at == 0 {
+      return mem::take(self);
+    }
+
+    let mut tail = List::new();
+
+    unsafe {
+      let mut split = self.front.expect("invariant violated: front is None");
+      for _ in 0..at {
+        split = (*split).to_b.expect("invariant violated: to_b is None");
+      }
+
+      let prev = (*split).to_f.expect("invariant violated: to_f is None");
+      (*prev).to_b = None;
+      (*split).to_f = None;
+
+      tail.front = Some(split);
+      tail.back = self.back;
+      tail.len = self.len - at;
+
+      self.back = Some(prev);
+      self.len = at;
+    }
+
+    tail
+  }
+
+  /// Split the list into `n` lists of consecutive elements, as close to
+  /// the same length as possible. When the elements don't divide evenly,
+  /// the earlier lists get one extra each. If there are fewer than `n`
+  /// elements, the later lists are empty. Reuses the existing nodes.
+  ///
+  /// Runs in O(n) space and O(n) time.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `n` is 0.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3, 4, 5].into_iter().collect();
+  /// let parts = dl.split_into(2);
+  ///
+  /// assert_eq!(format!("{:?}", parts), "[List[1, 2, 3], List[4, 5]]");
+  /// ```
+  pub fn split_into(self, n: usize) -> Vec<List<T>> {
+    assert!(n != 0, "number of lists must be nonzero");
+
+    let size = self.len / n;
+    let extra = self.len % n;
+
+    let mut parts = Vec::with_capacity(n);
+    let mut rest = self;
+
+    for i in 0..n {
+      let len = if i < extra { size + 1 } else { size };
+      let tail = rest.split_off(len);
+      parts.push(mem::replace(&mut rest, tail));
+    }
+
+    parts
+  }
+
+  /// Shorten the list to its first `len` elements, dropping the rest. Does
+  /// nothing if the list isn't longer than that.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// dl.truncate(1);
+  /// assert_eq!(format!("{:?}", dl), "List[1]");
+  ///
+  /// dl.truncate(5);
+  /// assert_eq!(format!("{:?}", dl), "List[1]");
+  /// ```
+  pub fn truncate(&mut self, len: usize) {
+    if len < self.len {
+      drop(self.split_off(len));
+    }
+  }
+
+  /// Move the first `n` elements to the back of the list, keeping their
+  /// order. No nodes are allocated; only the links at the ends and around
+  /// the `n`th element change.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `n` is greater than the length of the list.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3, 4].into_iter().collect();
+  ///
+  /// dl.rotate_left(1);
+  /// assert_eq!(format!("{:?}", dl), "List[2, 3, 4, 1]");
+  /// ```
+  pub fn rotate_left(&mut self, n: usize) {
+    assert!(n <= self.len, "rotation {} is out of bounds for length {}", n, self.len);
+
+    if n == 0 || n == self.len {
+      return;
+    }
+
+    unsafe {
+      let front = self.front.expect("invariant violated: front is None");
+      let back = self.back.expect("invariant violated: back is None");
+      let pivot = self.node_at(n).expect("invariant violated: node_at is None");
+      let prev = (*pivot).to_f.expect("invariant violated: to_f is None");
+
+      (*back).to_b = Some(front);
+      (*front).to_f = Some(back);
+      (*prev).to_b = None;
+      (*pivot).to_f = None;
+
+      self.front = Some(pivot);
+      self.back = Some(prev);
+    }
+  }
+
+  /// Move the last `n` elements to the front of the list, keeping their
+  /// order. No nodes are allocated; only the links at the ends and around
+  /// the `n`th element from the back change.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `n` is greater than the length of the list.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3, 4].into_iter().collect();
+  ///
+  /// dl.rotate_right(1);
+  /// assert_eq!(format!("{:?}", dl), "List[4, 1, 2, 3]");
+  /// ```
+  pub fn rotate_right(&mut self, n: usize) {
+    assert!(n <= self.len, "rotation {} is out of bounds for length {}", n, self.len);
+
+    let len = self.len;
+    self.rotate_left(len - n);
+  }
+
+  /// Get a reference to the element at `index`, counting from the front,
+  /// or an error saying how long the list actually is.
+  ///
+  /// Runs in O(1) space and O(n) time, walking from whichever end of the
+  /// list is closer.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::{List, IndexError};
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// assert_eq!(dl.try_get(1), Ok(&2));
+  ///
+  /// match dl.try_get(5) {
+  ///   Err(IndexError { index, len }) => assert_eq!((index, len), (5, 3)),
+  ///   Ok(_) => panic!("index 5 should be out of range")
+  /// };
+  /// ```
+  pub fn try_get(&self, index: usize) -> Result<&T, IndexError> {
+    if index >= self.len {
+      return Err(IndexError { index, len: self.len });
+    }
+
+    let found = if index < self.len / 2 {
+      self.iter().nth(index)
+    } else {
+      self.iter().rev().nth(self.len - 1 - index)
+    };
+
+    Ok(found.expect("invariant violated: len is wrong"))
+  }
+
+  /// Get a reference to the element at `index`, counting from the front.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// assert_eq!(dl.get(2), Some(&3));
+  /// assert_eq!(dl.get(3), None);
+  /// ```
+  pub fn get(&self, index: usize) -> Option<&T> {
+    self.try_get(index).ok()
+  }
+
+  /// Get a mutable reference to the element at `index`, counting from the
+  /// front.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// if let Some(n) = dl.get_mut(1) {
+  ///   *n = 20;
+  /// }
+  ///
+  /// assert_eq!(format!("{:?}", dl), "List[1, 20, 3]");
+  /// ```
+  pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+    self.node_at(index).map(|node| unsafe { &mut (*node).data })
+  }
+
+  pub fn iter(&self) -> Iter<T> {
+    Iter { 
+      front: self.front,
+      back: self.back,
+      len: self.len,
+      marker: PhantomData
+    }
+  }
+
+  pub fn iter_mut(&mut self) -> IterMut<T> {
+    IterMut {
+      front: self.front,
+      back: self.back,
+      len: self.len,
+      marker: PhantomData
+    }
+  }
+
+  /// Get a cursor on the first element of the list, or past the end if the
+  /// list is empty.
+  pub fn cursor_front_mut(&mut self) -> CursorMut<T> {
+    CursorMut {
+      current: self.front,
+      list: self
+    }
+  }
+
+  /// Get a cursor on the last element of the list, or past the end if the
+  /// list is empty.
+  pub fn cursor_back_mut(&mut self) -> CursorMut<T> {
+    CursorMut {
+      current: self.back,
+      list: self
+    }
+  }
+
+  /// Iterate over each pair of adjacent elements in the list, front to back.
+  /// Lists with fewer than two elements yield nothing.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  /// let pairs: Vec<(&u32, &u32)> = dl.iter_pairs().collect();
+  ///
+  /// assert_eq!(pairs, vec![(&1, &2), (&2, &3)]);
+  /// ```
+  pub fn iter_pairs(&self) -> impl Iterator<Item=(&T, &T)> {
+    self.iter().zip(self.iter().skip(1))
+  }
+
+  /// Iterate over every `step`-th element of the list, starting with the
+  /// front element.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `step` is 0.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3, 4, 5].into_iter().collect();
+  /// let sampled: Vec<&u32> = dl.iter_step(2).collect();
+  ///
+  /// assert_eq!(sampled, vec![&1, &3, &5]);
+  /// ```
+  pub fn iter_step(&self, step: usize) -> impl Iterator<Item=&T> {
+    self.iter().step_by(step)
+  }
+
+  /// Iterate over groups of `size` consecutive elements of the list, front
+  /// to back. If the length isn't a multiple of `size`, the leftover
+  /// elements at the back are skipped, and can be got with
+  /// `ChunksExact::remainder()`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `size` is 0.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3, 4, 5].into_iter().collect();
+  /// let mut chunks = dl.chunks_exact(2);
+  ///
+  /// assert_eq!(chunks.next(), Some(vec![&1, &2]));
+  /// assert_eq!(chunks.next(), Some(vec![&3, &4]));
+  /// assert_eq!(chunks.next(), None);
+  /// assert_eq!(chunks.remainder(), vec![&5]);
+  /// ```
+  pub fn chunks_exact(&self, size: usize) -> ChunksExact<T> {
+    assert!(size != 0, "chunk size must be nonzero");
+
+    ChunksExact {
+      iter: self.iter(),
+      size,
+      left: self.len - self.len % size
+    }
+  }
+
+  /// Iterate over every run of `size` consecutive elements of the list,
+  /// front to back. Runs overlap, so each one starts an element after the
+  /// last. If the list is shorter than `size`, nothing is yielded.
+  ///
+  /// Runs in O(size) space and O(n * size) time.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `size` is 0.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3, 4].into_iter().collect();
+  /// let mut windows = dl.windows(3);
+  ///
+  /// assert_eq!(windows.next(), Some(vec![&1, &2, &3]));
+  /// assert_eq!(windows.next(), Some(vec![&2, &3, &4]));
+  /// assert_eq!(windows.next(), None);
+  /// ```
+  pub fn windows(&self, size: usize) -> Windows<T> {
+    assert!(size != 0, "window size must be nonzero");
+
+    Windows {
+      iter: self.iter(),
+      size,
+      window: VecDeque::with_capacity(size)
+    }
+  }
+
+  /// Iterate over the list front to back, wrapping around to the front
+  /// again after reaching the back, for a total of `steps` elements.
+  /// An empty list yields nothing.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  /// let circular: Vec<&u32> = dl.iter_circular(5).collect();
+  ///
+  /// assert_eq!(circular, vec![&1, &2, &3, &1, &2]);
+  /// ```
+  pub fn iter_circular(&self, steps: usize) -> impl Iterator<Item=&T> {
+    self.iter().cycle().take(steps)
+  }
+}
+
+impl<T> List<T> {
+  /// Sort the list by splitting it in half, sorting each half, and merging
+  /// the results back together.
+  fn merge_sort<F>(&mut self, compare: &F) where
+    F: Fn(&T, &T) -> Ordering
+  {
+    if self.len < 2 {
+      return;
+    }
+
+    let mut back = self.split_off(self.len / 2);
+    self.merge_sort(compare);
+    back.merge_sort(compare);
+    self.merge_by(back, compare);
+  }
+
+  /// Merge the elements of `other` into the list, assuming both are
+  /// already sorted by `compare`. Elements of the list come before equal
+  /// elements of `other`.
+  fn merge_by<F>(&mut self, mut other: List<T>, compare: &F) where
+    F: Fn(&T, &T) -> Ordering
+  {
+    let mut merged = List::new();
+
+    unsafe {
+      while let (Some(ours), Some(theirs)) = (self.front, other.front) {
+        let node = if compare(&(*theirs).data, &(*ours).data) == Ordering::Less {
+          other.pop_front_node()
+        } else {
+          self.pop_front_node()
+        };
+
+        merged.push_back_node(node.expect("invariant violated: front is None"));
+      }
+    }
+
+    merged.append_back(self);
+    merged.append_back(&mut other);
+    mem::swap(self, &mut merged);
+  }
+
+  /// Find the node at index `at`, or `None` if `at` is past the end.
+  fn node_at(&self, at: usize) -> Option<*mut Node<T>> {
+    let mut here = self.front;
+
+    for _ in 0..at {
+      here = unsafe { (*here?).to_b };
+    }
+
+    here
+  }
+
+  /// Link all the nodes of `other` into the list between the adjacent
+  /// nodes `prev` and `next`, leaving `other` empty. `None` stands for the
+  /// respective end of the list.
+  unsafe fn link_between(&mut self,
+                         prev: Option<*mut Node<T>>,
+                         next: Option<*mut Node<T>>,
+                         other: &mut List<T>)
+  {
+    let (first, last) = match (other.front, other.back) {
+      (Some(first), Some(last)) => (first, last),
+      _ => return
+    };
+
+    (*first).to_f = prev;
+    (*last).to_b = next;
+
+    match prev {
+      None => self.front = Some(first),
+      Some(prev) => (*prev).to_b = Some(first)
+    };
+
+    match next {
+      None => self.back = Some(last),
+      Some(next) => (*next).to_f = Some(last)
+    };
+
+    self.len += other.len;
+
+    other.front = None;
+    other.back = None;
+    other.len = 0;
+  }
+
+  /// Link `element` into the list directly before the node `next`, or at
+  /// the very back if `next` is `None`. `next` must belong to this list.
+  unsafe fn insert_before_node(&mut self, next: Option<*mut Node<T>>, element: T) {
+    let next = match next {
+      None => return self.push_back(element),
+      Some(next) => next
+    };
+
+    let prev = match (*next).to_f {
+      None => return self.push_front(element),
+      Some(prev) => prev
+    };
+
+    let node = Box::new(Node { to_f: Some(prev), to_b: Some(next), data: element });
+    let node_ptr = Some(Box::into_raw(node));
+
+    (*prev).to_b = node_ptr;
+    (*next).to_f = node_ptr;
+    self.len += 1;
+  }
+
+  /// Unlink the first node in the list and return it, without freeing it.
+  unsafe fn pop_front_node(&mut self) -> Option<*mut Node<T>> {
+    let node = self.front?;
+
+    match (*node).to_b {
+      None => self.back = None,
+      Some(next) => (*next).to_f = None
+    };
+
+    self.front = (*node).to_b;
+    self.len -= 1;
+    (*node).to_b = None;
+
+    Some(node)
+  }
+
+  /// Unlink `node` from wherever it is in the list, free it, and return its
+  /// data. `node` must belong to this list.
+  unsafe fn unlink_node(&mut self, node: *mut Node<T>) -> T {
+    let node = Box::from_raw(node);
+
+    match node.to_f {
+      None => self.front = node.to_b,
+      Some(prev) => (*prev).to_b = node.to_b
+    };
+
+    match node.to_b {
+      None => self.back = node.to_f,
+      Some(next) => (*next).to_f = node.to_f
+    };
+
+    self.len -= 1;
+
+    node.data
+  }
+
+  /// Link an unlinked node in at the back of the list.
+  unsafe fn push_back_node(&mut self, node: *mut Node<T>) {
+    (*node).to_f = self.back;
+    (*node).to_b = None;
+
+    match self.back {
+      None => self.front = Some(node),
+      Some(back) => (*back).to_b = Some(node)
+    };
+
+    self.back = Some(node);
+    self.len += 1;
+  }
+}
+
+impl<T: PartialEq> List<T> {
+  /// Remove every element equal to `item`.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 1, 3].into_iter().collect();
+  ///
+  /// dl.remove_item(&1);
+  ///
+  /// assert_eq!(dl.iter().collect::<Vec<&u32>>(), vec![&2, &3]);
+  /// ```
+  pub fn remove_item(&mut self, item: &T) {
+    self.retain(|element| element != item);
+  }
+
+  /// Check if any element of the list is equal to `item`.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// assert!(dl.contains(&2));
+  /// assert!(!dl.contains(&4));
+  /// ```
+  pub fn contains(&self, item: &T) -> bool {
+    self.iter().any(|element| element == item)
+  }
+
+  /// Remove consecutive repeated elements, keeping the first of each run.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 1, 2, 1, 1].into_iter().collect();
+  ///
+  /// dl.dedup();
+  ///
+  /// assert_eq!(format!("{:?}", dl), "List[1, 2, 1]");
+  /// ```
+  pub fn dedup(&mut self) {
+    self.dedup_by(|a, b| a == b);
+  }
+
+  /// Move the first element equal to `value` so that it ends up at index
+  /// `to`, shifting the elements in between over by one. Returns whether
+  /// there was such an element; if not, the list is left alone.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Panics
+  ///
+  /// Panics if an element is found but `to` isn't less than the length of
+  /// the list.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// assert!(dl.move_value(&2, 0));
+  /// assert_eq!(format!("{:?}", dl), "List[2, 1, 3]");
+  ///
+  /// assert!(!dl.move_value(&4, 0));
+  /// ```
+  pub fn move_value(&mut self, value: &T, to: usize) -> bool {
+    let mut here = self.front;
+
+    unsafe {
+      while let Some(node) = here {
+        if (*node).data == *value {
+          assert!(to < self.len, "index {} is out of bounds for length {}", to, self.len);
+
+          let element = self.unlink_node(node);
+          let next = self.node_at(to);
+          self.insert_before_node(next, element);
+          return true;
+        }
+
+        here = (*node).to_b;
+      }
+    }
+
+    false
+  }
+}
+
+impl<A, B> List<(A, B)> {
+  /// Split a list of pairs into a list of the first elements and a list
+  /// of the second elements, in the same order.
+  ///
+  /// Runs in O(n) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<(u32, &str)> = vec![(1, "a"), (2, "b")].into_iter().collect();
+  /// let (numbers, letters) = dl.unzip();
+  ///
+  /// assert_eq!(numbers.iter().collect::<Vec<&u32>>(), vec![&1, &2]);
+  /// assert_eq!(letters.iter().collect::<Vec<&&str>>(), vec![&"a", &"b"]);
+  /// ```
+  pub fn unzip(self) -> (List<A>, List<B>) {
+    let mut firsts = List::new();
+    let mut seconds = List::new();
+
+    for (a, b) in self {
+      firsts.push_back(a);
+      seconds.push_back(b);
+    }
+
+    (firsts, seconds)
+  }
+}
+
+impl fmt::Display for IndexError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "index {} is out of range for a list of length {}", self.index, self.len)
+  }
+}
+
+impl Error for IndexError {
+  fn description(&self) -> &str {
+    "index out of range"
+  }
+}
+
+// The raw pointers stop `Send` and `Sync` from being derived, but a list
+// owns its nodes exactly as a chain of `Box<Node<T>>` would: nothing else
+// can reach them, and `&List<T>` only ever hands out `&T`. So it's as safe
+// to send or share as the elements themselves are.
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Sync> Sync for List<T> {}
+
+impl<T> Drop for List<T> {
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
+
+impl<T> Default for List<T> {
+  /// An empty list, as with `List::new()`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let list: List<u32> = Default::default();
+  ///
+  /// assert!(list.is_empty());
+  /// ```
+  fn default() -> Self {
+    List::new()
+  }
+}
+
+impl<T> Index<usize> for List<T> {
+  type Output = T;
+
+  /// # Panics
+  ///
+  /// Panics if `index` is past the end of the list.
+  fn index(&self, index: usize) -> &T {
+    match self.try_get(index) {
+      Ok(element) => element,
+      Err(err) => panic!("{}", err)
+    }
+  }
+}
+
+impl<T> IndexMut<usize> for List<T> {
+  /// # Panics
+  ///
+  /// Panics if `index` is past the end of the list.
+  fn index_mut(&mut self, index: usize) -> &mut T {
+    let len = self.len;
+    match self.get_mut(index) {
+      Some(element) => element,
+      None => panic!("{}", IndexError { index, len })
+    }
+  }
+}
+
+impl<T: Clone> Clone for List<T> {
+  /// Copy every element into a new, independent list.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2].into_iter().collect();
+  /// let mut copy = dl.clone();
+  ///
+  /// copy.push_back(3);
+  ///
+  /// assert_eq!(dl.len(), 2);
+  /// assert_eq!(copy.len(), 3);
+  /// ```
+  fn clone(&self) -> Self {
+    self.iter().cloned().collect()
+  }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+  /// Lists are equal when they have the same length and their elements
+  /// are equal in order.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl1: List<u32> = vec![1, 2].into_iter().collect();
+  /// let dl2: List<u32> = vec![1, 2].into_iter().collect();
+  ///
+  /// assert!(dl1 == dl2);
+  /// ```
+  fn eq(&self, other: &Self) -> bool {
+    self.len == other.len && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+  }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// assert_eq!(format!("{:?}", dl), "List[1, 2, 3]");
+  /// ```
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("List")?;
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Node<T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("Node").field("data", &self.data).finish()
+  }
+}
+
+impl<T> IntoIterator for List<T> {
+  type Item = T;
+  type IntoIter = IntoIter<T>;
+
+  fn into_iter(self) -> IntoIter<T> {
+    IntoIter {
+      list: self
+    }
+  }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<&'a T> {
+    unsafe {
+      if self.len == 0 { return None; }
+
+      let node = self.front
+        .expect("invariant violated: front is None");
+
+      self.len -= 1;
+      self.front = (*node).to_b;
+
+      Some(&(*node).data)
+    }
+  }
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+  fn clone(&self) -> Self {
+    Iter {
+      front: self.front,
+      back: self.back,
+      len: self.len,
+      marker: PhantomData
+    }
+  }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+  fn len(&self) -> usize {
+    self.len
+  }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+  fn next_back(&mut self) -> Option<&'a T> {
+    unsafe {
+      if self.len == 0 { return None; }
+
+      let node = self.back
+        .expect("invariant violated: back is None");
+
+      self.len -= 1;
+      self.back = (*node).to_f;
+
+      Some(&(*node).data)
+    }
+  }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+  type Item = &'a mut T;
+
+  fn next(&mut self) -> Option<&'a mut T> {
+    unsafe {
+      if self.len == 0 { return None; }
+
+      let node = self.front
+        .expect("invariant violated: front is None");
+
+      self.len -= 1;
+      self.front = (*node).to_b;
+
+      Some(&mut (*node).data)
+    }
+  }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {
+  fn len(&self) -> usize {
+    self.len
+  }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+  fn next_back(&mut self) -> Option<&'a mut T> {
+    unsafe {
+      if self.len == 0 { return None; }
+
+      let node = self.back
+        .expect("invariant violated: back is None");
+
+      self.len -= 1;
+      self.back = (*node).to_f;
+
+      Some(&mut (*node).data)
+    }
+  }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+  /// The element the cursor is on, or `None` if it's past the end.
+  pub fn current(&mut self) -> Option<&mut T> {
+    unsafe { self.current.map(|node| &mut (*node).data) }
+  }
+
+  /// Move to the next element. Moving from the last element puts the
+  /// cursor past the end, and moving from there wraps around to the front.
+  pub fn move_next(&mut self) {
+    self.current = match self.current {
+      Some(node) => unsafe { (*node).to_b },
+      None => self.list.front
+    };
+  }
+
+  /// Move to the previous element. Moving from the first element puts the
+  /// cursor past the end, and moving from there wraps around to the back.
+  pub fn move_prev(&mut self) {
+    self.current = match self.current {
+      Some(node) => unsafe { (*node).to_f },
+      None => self.list.back
+    };
+  }
+
+  /// Advance from the current position to the first element satisfying
+  /// `pred`, including the current one. Returns whether there was one; if
+  /// not, the cursor is left past the end.
+  ///
+  /// Runs in O(1) space and O(n) time.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 3, 4, 5].into_iter().collect();
+  ///
+  /// {
+  ///   let mut cursor = dl.cursor_front_mut();
+  ///   assert!(cursor.seek(|n| n % 2 == 0));
+  ///   assert_eq!(cursor.current(), Some(&mut 4));
+  ///   assert!(!cursor.seek(|n| *n > 10));
+  ///   assert_eq!(cursor.current(), None);
+  /// }
+  /// ```
+  pub fn seek<F>(&mut self, mut pred: F) -> bool where
+    F: FnMut(&T) -> bool
+  {
+    unsafe {
+      while let Some(node) = self.current {
+        if pred(&(*node).data) {
+          return true;
+        }
+        self.current = (*node).to_b;
+      }
+    }
+
+    false
+  }
+
+  /// Insert `element` directly before the cursor, or at the back of the
+  /// list if the cursor is past the end. The cursor stays where it is.
+  pub fn insert_before(&mut self, element: T) {
+    unsafe { self.list.insert_before_node(self.current, element) }
+  }
+
+  /// Insert `element` directly after the cursor, or at the front of the
+  /// list if the cursor is past the end. The cursor stays where it is.
+  pub fn insert_after(&mut self, element: T) {
+    match self.current {
+      Some(node) => unsafe {
+        let next = (*node).to_b;
+        self.list.insert_before_node(next, element)
+      },
+      None => self.list.push_front(element)
+    };
+  }
+
+  /// Remove the element the cursor is on and return it, moving the cursor
+  /// to the next element. Returns `None` if the cursor is past the end.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  ///
+  /// let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  ///
+  /// {
+  ///   let mut cursor = dl.cursor_back_mut();
+  ///   cursor.move_prev();
+  ///   assert_eq!(cursor.remove_current(), Some(2));
+  ///   cursor.insert_after(4);
+  ///   assert_eq!(cursor.current(), Some(&mut 3));
+  /// }
+  ///
+  /// assert_eq!(format!("{:?}", dl), "List[1, 3, 4]");
+  /// ```
+  pub fn remove_current(&mut self) -> Option<T> {
+    let node = self.current?;
+
+    unsafe {
+      self.current = (*node).to_b;
+      Some(self.list.unlink_node(node))
+    }
+  }
+}
+
+impl<'a, T> ChunksExact<'a, T> {
+  /// The elements at the back of the list which don't make up a full
+  /// group.
+  pub fn remainder(&self) -> Vec<&'a T> {
+    self.iter.clone().skip(self.left).collect()
+  }
+}
+
+impl<'a, T> Iterator for ChunksExact<'a, T> {
+  type Item = Vec<&'a T>;
+
+  fn next(&mut self) -> Option<Vec<&'a T>> {
+    if self.left == 0 {
+      return None;
+    }
+
+    self.left -= self.size;
+    Some(self.iter.by_ref().take(self.size).collect())
+  }
+}
+
+impl<'a, T> Iterator for Windows<'a, T> {
+  type Item = Vec<&'a T>;
+
+  fn next(&mut self) -> Option<Vec<&'a T>> {
+    while self.window.len() < self.size {
+      self.window.push_back(self.iter.next()?);
+    }
+
+    let window = self.window.iter().cloned().collect();
+    self.window.pop_front();
+    Some(window)
+  }
+}
+
+impl<T> Iterator for IntoIter<T> {
+  type Item = T;
+
+  fn next(&mut self) -> Option<T> {
+    self.list.pop_front()
+  }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+  fn len(&self) -> usize {
+    self.list.len
+  }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+  fn next_back(&mut self) -> Option<T> {
+    self.list.pop_back()
+  }
+}
+
+impl<T> Iterator for Drain<T> {
+  type Item = T;
+
+  fn next(&mut self) -> Option<T> {
+    self.list.pop_front()
+  }
+}
+
+impl<T> ExactSizeIterator for Drain<T> {
+  fn len(&self) -> usize {
+    self.list.len
+  }
+}
+
+impl<T> DoubleEndedIterator for Drain<T> {
+  fn next_back(&mut self) -> Option<T> {
+    self.list.pop_back()
+  }
+}
+
+impl<'a, T, F> Iterator for DrainFilter<'a, T, F> where
+  F: FnMut(&T) -> bool
+{
+  type Item = T;
+
+  fn next(&mut self) -> Option<T> {
+    while let Some(node) = self.next {
+      unsafe {
+        self.next = (*node).to_b;
+
+        if (self.pred)(&(*node).data) {
+          return Some(self.list.unlink_node(node));
+        }
+      }
+    }
+
+    None
+  }
+}
+
+impl<'a, T, F> Drop for DrainFilter<'a, T, F> where
+  F: FnMut(&T) -> bool
+{
+  fn drop(&mut self) {
+    self.for_each(drop);
+  }
+}
+
+impl<T> Extend<T> for List<T> {
+  /// Same as `List::extend_back()`.
+  fn extend<I>(&mut self, iter: I) where
+    I: IntoIterator<Item=T>
+  {
+    self.extend_back(iter);
+  }
+}
+
+impl<A> FromIterator<A> for List<A>
+{
+  /// # Examples
+  ///
+  /// Using it directly:
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  /// use std::iter::{IntoIterator, FromIterator};
+  ///
+  /// let numbers: Vec<u32> = vec![1, 2, 3, 4];
+  /// let list = List::from_iter(numbers);
+  ///
+  /// assert_eq!(list.len(), 4);
+  ///
+  /// let mut iter = list.into_iter();
+  /// assert_eq!(iter.next(), Some(1));
+  /// assert_eq!(iter.next(), Some(2));
+  /// assert_eq!(iter.next(), Some(3));
+  /// assert_eq!(iter.next(), Some(4));
+  /// assert_eq!(iter.next(), None);
+  /// ```
+  ///
+  /// Through `collect()`:
+  ///
+  /// ```
+  /// use kaiseki::list::List;
+  /// use std::iter::IntoIterator;
+  ///
+  /// let numbers: Vec<u32> = vec![1, 2, 3, 4];
+  /// let list: List<u32> = numbers.into_iter().collect();
+  ///
+  /// assert_eq!(list.len(), 4);
+  ///
+  /// let mut iter = list.into_iter();
+  /// assert_eq!(iter.next(), Some(1));
+  /// assert_eq!(iter.next(), Some(2));
+  /// assert_eq!(iter.next(), Some(3));
+  /// assert_eq!(iter.next(), Some(4));
+  /// assert_eq!(iter.next(), None);
+  /// ```
+  fn from_iter<I>(iter: I) -> Self where
     I: IntoIterator<Item=A>
   {
     let mut result = List::new();
@@ -462,3 +2839,52 @@ impl<A> FromIterator<A> for List<A>
     result
   }
 }
+
+/// Lists are serialized as sequences, the same as `Vec`s, when the `serde`
+/// feature is enabled.
+#[cfg(feature = "serde")]
+mod serialization {
+  use std::fmt;
+  use std::marker::PhantomData;
+
+  use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+  use serde::ser::{Serialize, Serializer};
+
+  use super::List;
+
+  impl<T: Serialize> Serialize for List<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where
+      S: Serializer
+    {
+      serializer.collect_seq(self.iter())
+    }
+  }
+
+  impl<'de, T: Deserialize<'de>> Deserialize<'de> for List<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where
+      D: Deserializer<'de>
+    {
+      deserializer.deserialize_seq(ListVisitor(PhantomData))
+    }
+  }
+
+  struct ListVisitor<T>(PhantomData<T>);
+
+  impl<'de, T: Deserialize<'de>> Visitor<'de> for ListVisitor<T> {
+    type Value = List<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      f.write_str("a sequence")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<List<T>, A::Error> where
+      A: SeqAccess<'de>
+    {
+      let mut list = List::new();
+      while let Some(element) = seq.next_element()? {
+        list.push_back(element);
+      }
+      Ok(list)
+    }
+  }
+}
@@ -21,7 +21,13 @@ enum Token {
   AnchorStart,
   AnchorEnd,
   AnchorOp(Op),
-  AnchorOpArg(String)
+  AnchorOpArg(String),
+  /// The argument of a `comment`, which can contain any text.
+  AnchorText(String),
+  NowebStart,
+  NowebEnd,
+  NowebDefine,
+  NowebName(String)
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -29,7 +35,17 @@ enum Op {
   Insert,
   Before,
   After,
-  Label
+  Label,
+  Source,
+  Comment,
+  Duplicate,
+  Replace,
+  Delete,
+  Toc,
+  Raw,
+  EndRaw,
+  Timestamp,
+  Revision
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -37,7 +53,76 @@ pub enum Anchor {
   Insert,
   Before(String),
   After(String),
-  Label(String)
+  Label(String),
+  Source(String),
+  Comment(String),
+  Duplicate(String),
+  Replace(String),
+  Delete(String),
+  Toc,
+  Raw,
+  EndRaw,
+  Timestamp,
+  Revision
+}
+
+impl Anchor {
+  /// The name of the command used in the anchor, as written in the
+  /// native syntax.
+  pub fn op_name(&self) -> &'static str {
+    match *self {
+      Anchor::Insert => "insert",
+      Anchor::Before(_) => "before",
+      Anchor::After(_) => "after",
+      Anchor::Label(_) => "label",
+      Anchor::Source(_) => "source",
+      Anchor::Comment(_) => "comment",
+      Anchor::Duplicate(_) => "duplicate",
+      Anchor::Replace(_) => "replace",
+      Anchor::Delete(_) => "delete",
+      Anchor::Toc => "toc",
+      Anchor::Raw => "raw",
+      Anchor::EndRaw => "endraw",
+      Anchor::Timestamp => "timestamp",
+      Anchor::Revision => "revision"
+    }
+  }
+}
+
+thread_local! {
+  /// Compiled once, since every line of input is checked against it.
+  static NOWEB_ANCHOR: Regex = Regex::new(r"<<[^>]+>>=?").unwrap();
+}
+
+/// The different ways that anchors can be written in source files.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AnchorSyntax {
+  /// Kaiseki's own syntax, e.g. `##[label(name)]`.
+  Native,
+  /// noweb-style chunks. `<<name>>=` places the following lines after the
+  /// label `name`, and `<<name>>` on its own creates the label `name`.
+  Noweb
+}
+
+impl AnchorSyntax {
+  /// Check if the line *might* contain an anchor written in this syntax.
+  pub fn might_be_anchor<'a>(&self, line: &'a str) -> Option<Match<'a>> {
+    match *self {
+      AnchorSyntax::Native => might_be_anchor(line),
+      AnchorSyntax::Noweb => NOWEB_ANCHOR.with(|anchor| anchor.find(line))
+    }
+  }
+
+  /// Attempt to parse the given string as an anchor written in this syntax.
+  pub fn parse(&self, text: &str) -> Result<Anchor> {
+    match *self {
+      AnchorSyntax::Native => parse(text),
+      AnchorSyntax::Noweb => {
+        let lex_result = lex_noweb_tokens(text)?;
+        parse_noweb_anchor(lex_result)
+      }
+    }
+  }
 }
 
 /// Attempt to parse the given string as a Kaiseki anchor.
@@ -64,10 +149,22 @@ macro_rules! check_next {
   }}
 }
 
+/// Split a reference to a label like `file.rs:name` into the file it's
+/// qualified with, if there is one, and the name of the label.
+pub fn split_qualified(reference: &str) -> (Option<&str>, &str) {
+  match reference.rfind(':') {
+    Some(i) => (Some(reference[..i].trim()), reference[i + 1..].trim()),
+    None => (None, reference)
+  }
+}
+
 /// Check if the line *might* contain an anchor. Returns the matching
 /// string, so that it can then be formally checked with a parser.
+///
+/// An argument runs up to the first `)]`, so that the text of a comment
+/// can contain brackets.
 pub fn might_be_anchor(line: &str) -> Option<Match> {
-  let anchor = Regex::new(r"##\[[^]]+\]").unwrap();
+  let anchor = Regex::new(r"##\[[^]()]*\(.*?\)\s*\]|##\[[^]]+\]").unwrap();
 
   anchor.find(line)
 }
@@ -101,7 +198,65 @@ fn parse_op(tokens: &mut VecDeque<Token>) -> Result<Anchor> {
       let arg = parse_arg(tokens)?;
       parse_end(tokens)?;
 
+      // `:` separates a file from a label name in references to labels.
+      if arg.contains(':') { bail!(ErrorKind::ParseError); }
+
       Ok(Anchor::Label(arg))
+    },
+    Token::AnchorOp(Op::Source) => {
+      let arg = parse_arg(tokens)?;
+      parse_end(tokens)?;
+
+      Ok(Anchor::Source(arg))
+    },
+    Token::AnchorOp(Op::Comment) => {
+      let text = parse_text(tokens)?;
+      parse_end(tokens)?;
+
+      Ok(Anchor::Comment(text))
+    },
+    Token::AnchorOp(Op::Duplicate) => {
+      let arg = parse_arg(tokens)?;
+      parse_end(tokens)?;
+
+      Ok(Anchor::Duplicate(arg))
+    },
+    Token::AnchorOp(Op::Replace) => {
+      let arg = parse_arg(tokens)?;
+      parse_end(tokens)?;
+
+      Ok(Anchor::Replace(arg))
+    },
+    Token::AnchorOp(Op::Delete) => {
+      let arg = parse_arg(tokens)?;
+      parse_end(tokens)?;
+
+      Ok(Anchor::Delete(arg))
+    },
+    Token::AnchorOp(Op::Toc) => {
+      parse_end(tokens)?;
+
+      Ok(Anchor::Toc)
+    },
+    Token::AnchorOp(Op::Raw) => {
+      parse_end(tokens)?;
+
+      Ok(Anchor::Raw)
+    },
+    Token::AnchorOp(Op::EndRaw) => {
+      parse_end(tokens)?;
+
+      Ok(Anchor::EndRaw)
+    },
+    Token::AnchorOp(Op::Timestamp) => {
+      parse_end(tokens)?;
+
+      Ok(Anchor::Timestamp)
+    },
+    Token::AnchorOp(Op::Revision) => {
+      parse_end(tokens)?;
+
+      Ok(Anchor::Revision)
     }
   })
 }
@@ -109,11 +264,31 @@ fn parse_op(tokens: &mut VecDeque<Token>) -> Result<Anchor> {
 fn parse_arg(tokens: &mut VecDeque<Token>) -> Result<String> {
   check_next!(tokens {
     Token::AnchorOpArg(str) => {
-      Ok(str)
+      // Names are stored without any whitespace around them, so that
+      // `( foo )` matches `(foo)`.
+      let name = str.trim();
+
+      if name.is_empty() { bail!(ErrorKind::ParseError); }
+
+      Ok(name.to_string())
     }
   })
 }
 
+/// Like `parse_arg()`, but for arguments that aren't names, which can
+/// contain any text at all.
+fn parse_text(tokens: &mut VecDeque<Token>) -> Result<String> {
+  let text = check_next!(tokens {
+    Token::AnchorOpArg(str) => { str },
+    Token::AnchorText(str) => { str }
+  });
+  let text = text.trim();
+
+  if text.is_empty() { bail!(ErrorKind::ParseError); }
+
+  Ok(text.to_string())
+}
+
 fn parse_end(tokens: &mut VecDeque<Token>) -> Result<()> {
   check_next!(tokens {
     Token::AnchorEnd => { }
@@ -122,10 +297,37 @@ fn parse_end(tokens: &mut VecDeque<Token>) -> Result<()> {
   Ok(())
 }
 
+fn parse_noweb_anchor(mut tokens: VecDeque<Token>) -> Result<Anchor> {
+  check_next!(tokens {
+    Token::NowebStart => { }
+  });
+
+  let name = check_next!(tokens {
+    Token::NowebName(name) => { name }
+  });
+  if name.is_empty() { bail!(ErrorKind::ParseError); }
+
+  let anchor = check_next!(tokens {
+    Token::NowebDefine => { Anchor::After(name) },
+    Token::NowebEnd => { Anchor::Label(name) }
+  });
+
+  if !tokens.is_empty() { bail!(ErrorKind::ParseError); }
+
+  Ok(anchor)
+}
+
 /// For now, we assume that every regular expression passed in has
 /// a '^' anchor at the beginning. Otherwise, bad things will happen.
+///
+/// Tokens which can't be described by a regular expression can be given
+/// after a `;` as scanners: functions returning the length of the token
+/// at the start of their input, if there is one.
 macro_rules! lexer {
   ($($regex:expr => $out:expr),+) => {
+    lexer!($($regex => $out),+ ;)
+  };
+  ($($regex:expr => $out:expr),+ ; $($scanner:expr => $scanner_out:expr),*) => {
     |lexing: &str| {
       let mut chars = &lexing[..];
       let lexers: Vec<(Regex, Box<Fn(&str) -> Token>)> = vec![
@@ -134,6 +336,9 @@ macro_rules! lexer {
           (regex, Box::new($out))
         }),+
       ];
+      let scanners: Vec<(fn(&str) -> Option<usize>, Box<Fn(&str) -> Token>)> = vec![
+        $(($scanner, Box::new($scanner_out))),*
+      ];
       let mut tokens = VecDeque::new();
 
       while !chars.is_empty() {
@@ -151,6 +356,15 @@ macro_rules! lexer {
           }
         }
 
+        for &(scanner, ref out) in &scanners {
+          if let Some(end) = scanner(chars) {
+            if end > max_match {
+              max_match = end;
+              max_token = out(&chars[..end]);
+            }
+          }
+        }
+
         if max_match == 0 { bail!(ErrorKind::LexError); }
 
         chars = &chars[max_match..];
@@ -170,7 +384,77 @@ fn lex_tokens(chars: &str) -> Result<VecDeque<Token>> {
     r"^after" => |_| Token::AnchorOp(Op::After),
     r"^insert" => |_| Token::AnchorOp(Op::Insert),
     r"^label" => |_| Token::AnchorOp(Op::Label),
-    r"^\([\w\d\s\-]+\)" => |str| Token::AnchorOpArg(str.to_string())
+    r"^source" => |_| Token::AnchorOp(Op::Source),
+    r"^comment" => |_| Token::AnchorOp(Op::Comment),
+    r"^duplicate" => |_| Token::AnchorOp(Op::Duplicate),
+    r"^replace" => |_| Token::AnchorOp(Op::Replace),
+    r"^delete" => |_| Token::AnchorOp(Op::Delete),
+    r"^toc" => |_| Token::AnchorOp(Op::Toc),
+    r"^raw" => |_| Token::AnchorOp(Op::Raw),
+    r"^endraw" => |_| Token::AnchorOp(Op::EndRaw),
+    r"^timestamp" => |_| Token::AnchorOp(Op::Timestamp),
+    r"^revision" => |_| Token::AnchorOp(Op::Revision);
+    // Arguments are stored without their surrounding parentheses, so that
+    // names match up with names written in other syntaxes.
+    scan_arg => |str| Token::AnchorOpArg(str[1..str.len() - 1].to_string()),
+    scan_text => |str| Token::AnchorText(str[1..str.len() - 1].to_string())
+  };
+
+  lexer(chars)
+}
+
+/// Anchor arguments are wrapped in parentheses, and may contain further
+/// balanced parentheses, e.g. `(foo(bar))`.
+fn scan_arg(chars: &str) -> Option<usize> {
+  if !chars.starts_with('(') { return None; }
+
+  let mut depth = 0;
+
+  for (i, c) in chars.char_indices() {
+    match c {
+      '(' => depth += 1,
+      ')' => {
+        depth -= 1;
+        if depth == 0 {
+          // Empty arguments aren't allowed.
+          return if i > 1 { Some(i + 1) } else { None };
+        }
+      },
+      c if c.is_alphanumeric() || c.is_whitespace() || "_-./:".contains(c) => (),
+      _ => return None
+    };
+  }
+
+  None
+}
+
+/// Text arguments are wrapped in balanced parentheses too, but can contain
+/// any other characters, e.g. `(see foo, bar!)`.
+fn scan_text(chars: &str) -> Option<usize> {
+  if !chars.starts_with('(') { return None; }
+
+  let mut depth = 0;
+
+  for (i, c) in chars.char_indices() {
+    match c {
+      '(' => depth += 1,
+      ')' => {
+        depth -= 1;
+        if depth == 0 { return Some(i + 1); }
+      },
+      _ => ()
+    };
+  }
+
+  None
+}
+
+fn lex_noweb_tokens(chars: &str) -> Result<VecDeque<Token>> {
+  let lexer = lexer! {
+    r"^<<" => |_| Token::NowebStart,
+    r"^>>" => |_| Token::NowebEnd,
+    r"^>>=" => |_| Token::NowebDefine,
+    r"^[\w\d\s\-\./]+" => |str| Token::NowebName(str.trim().to_string())
   };
 
   lexer(chars)
@@ -178,9 +462,9 @@ fn lex_tokens(chars: &str) -> Result<VecDeque<Token>> {
 
 #[cfg(test)]
 mod parsing_tests {
-  use super::Anchor;
+  use super::{Anchor, AnchorSyntax};
   use super::might_be_anchor;
-  use super::{lex_tokens, parse_anchor};
+  use super::{lex_tokens, parse_anchor, split_qualified};
 
   #[test]
   fn test_might_be_anchor_1() {
@@ -202,6 +486,27 @@ mod parsing_tests {
     assert_eq!(result.as_str(), "##[insert]");
   }
 
+  #[test]
+  fn test_might_be_anchor_3() {
+    let str = "// ##[comment(see [1] here)] and more";
+    let result = might_be_anchor(str);
+
+    assert!(result.is_some());
+    let result = result.unwrap();
+    assert_eq!(result.as_str(), "##[comment(see [1] here)]");
+  }
+
+  #[test]
+  fn test_might_be_anchor_4() {
+    // Still found, so that it can be reported as malformed.
+    let str = "// ##[label(foo]";
+    let result = might_be_anchor(str);
+
+    assert!(result.is_some());
+    let result = result.unwrap();
+    assert_eq!(result.as_str(), "##[label(foo]");
+  }
+
   #[test]
   fn test_might_be_anchor_failure_1() {
     let str = "#[macro_use]";
@@ -235,7 +540,7 @@ mod parsing_tests {
     let lex_result = lex_tokens(str).unwrap();
     let parse_result = parse_anchor(lex_result).unwrap();
 
-    assert_eq!(parse_result, Anchor::Before("(Something Else)".to_string()));
+    assert_eq!(parse_result, Anchor::Before("Something Else".to_string()));
   }
 
   #[test]
@@ -244,7 +549,7 @@ mod parsing_tests {
     let lex_result = lex_tokens(str).unwrap();
     let parse_result = parse_anchor(lex_result).unwrap();
 
-    assert_eq!(parse_result, Anchor::After("(kebab-case)".to_string()));
+    assert_eq!(parse_result, Anchor::After("kebab-case".to_string()));
   }
 
   #[test]
@@ -253,7 +558,205 @@ mod parsing_tests {
     let lex_result = lex_tokens(str).unwrap();
     let parse_result = parse_anchor(lex_result).unwrap();
 
-    assert_eq!(parse_result, Anchor::Label("(label)".to_string()));
+    assert_eq!(parse_result, Anchor::Label("label".to_string()));
+  }
+
+  #[test]
+  fn test_parse_anchor_5() {
+    let str = "##[source(src/main.rs)]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::Source("src/main.rs".to_string()));
+  }
+
+  #[test]
+  fn test_parse_noweb_anchor_1() {
+    let str = "<<Main Loop>>=";
+    let parse_result = AnchorSyntax::Noweb.parse(str).unwrap();
+
+    assert_eq!(parse_result, Anchor::After("Main Loop".to_string()));
+  }
+
+  #[test]
+  fn test_parse_noweb_anchor_2() {
+    let str = "<<Main Loop>>";
+    let parse_result = AnchorSyntax::Noweb.parse(str).unwrap();
+
+    assert_eq!(parse_result, Anchor::Label("Main Loop".to_string()));
+  }
+
+  #[test]
+  fn test_parse_noweb_anchor_3() {
+    let str = "<< Main Loop >>=";
+    let parse_result = AnchorSyntax::Noweb.parse(str).unwrap();
+
+    assert_eq!(parse_result, Anchor::After("Main Loop".to_string()));
+  }
+
+  #[test]
+  fn test_parse_noweb_anchor_fail_1() {
+    let str = "<<>>=";
+
+    assert!(AnchorSyntax::Noweb.parse(str).is_err());
+  }
+
+  #[test]
+  fn test_parse_noweb_anchor_fail_2() {
+    let str = "<<  >>";
+
+    assert!(AnchorSyntax::Noweb.parse(str).is_err());
+  }
+
+  #[test]
+  fn test_might_be_noweb_anchor() {
+    let str = "  <<Main Loop>>=";
+    let result = AnchorSyntax::Noweb.might_be_anchor(str);
+
+    assert!(result.is_some());
+    assert_eq!(result.unwrap().as_str(), "<<Main Loop>>=");
+    assert!(AnchorSyntax::Native.might_be_anchor(str).is_none());
+  }
+
+  #[test]
+  fn test_parse_anchor_6() {
+    let str = "##[label(foo(bar))]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::Label("foo(bar)".to_string()));
+  }
+
+  #[test]
+  fn test_parse_anchor_7() {
+    let str = "##[comment(section start)]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::Comment("section start".to_string()));
+  }
+
+  #[test]
+  fn test_parse_anchor_8() {
+    let str = "##[label( foo )]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::Label("foo".to_string()));
+  }
+
+  #[test]
+  fn test_parse_anchor_9() {
+    let str = "##[after(  Has Spaces )]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::After("Has Spaces".to_string()));
+  }
+
+  #[test]
+  fn test_parse_anchor_10() {
+    let str = "##[duplicate(Main Loop)]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::Duplicate("Main Loop".to_string()));
+  }
+
+  #[test]
+  fn test_parse_anchor_11() {
+    let str = "##[after(foo-bar)]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::After("foo-bar".to_string()));
+  }
+
+  #[test]
+  fn test_parse_anchor_12() {
+    let str = "##[replace(Main Loop)]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::Replace("Main Loop".to_string()));
+  }
+
+  #[test]
+  fn test_parse_anchor_13() {
+    let str = "##[delete(Main Loop)]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::Delete("Main Loop".to_string()));
+  }
+
+  #[test]
+  fn test_parse_anchor_14() {
+    let str = "##[after(file1.lp:section)]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::After("file1.lp:section".to_string()));
+  }
+
+  #[test]
+  fn test_parse_anchor_15() {
+    let str = "##[toc]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::Toc);
+  }
+
+  #[test]
+  fn test_parse_anchor_16() {
+    let raw = parse_anchor(lex_tokens("##[raw]").unwrap()).unwrap();
+    let end = parse_anchor(lex_tokens("##[endraw]").unwrap()).unwrap();
+
+    assert_eq!((raw, end), (Anchor::Raw, Anchor::EndRaw));
+  }
+
+  #[test]
+  fn test_parse_anchor_17() {
+    let str = "##[timestamp]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::Timestamp);
+  }
+
+  #[test]
+  fn test_parse_anchor_18() {
+    let str = "##[revision]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::Revision);
+  }
+
+  #[test]
+  fn test_parse_anchor_19() {
+    let str = "##[comment(see foo, bar!)]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::Comment("see foo, bar!".to_string()));
+  }
+
+  #[test]
+  fn test_parse_anchor_20() {
+    let str = "##[comment(TODO: handle \"quoted\" names; see #12 (maybe?))]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result).unwrap();
+
+    assert_eq!(parse_result, Anchor::Comment("TODO: handle \"quoted\" names; see #12 (maybe?)".to_string()));
+  }
+
+  #[test]
+  fn test_split_qualified() {
+    assert_eq!(split_qualified("file1.lp:section"), (Some("file1.lp"), "section"));
+    assert_eq!(split_qualified("src/main.rs: Main Loop"), (Some("src/main.rs"), "Main Loop"));
+    assert_eq!(split_qualified("section"), (None, "section"));
   }
 
   #[test]
@@ -273,6 +776,61 @@ mod parsing_tests {
 
     assert!(parse_result.is_err());
   }
+
+  #[test]
+  fn test_parse_anchor_fail_3() {
+    let str = "##[label(   )]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result);
+
+    assert!(parse_result.is_err());
+  }
+
+  #[test]
+  fn test_parse_anchor_fail_4() {
+    let str = "##[label(file1.lp:section)]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result);
+
+    assert!(parse_result.is_err());
+  }
+
+  #[test]
+  fn test_parse_anchor_fail_5() {
+    let str = "##[toc(labels)]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result);
+
+    assert!(parse_result.is_err());
+  }
+
+  #[test]
+  fn test_parse_anchor_fail_6() {
+    let str = "##[revision(HEAD)]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result);
+
+    assert!(parse_result.is_err());
+  }
+
+  #[test]
+  fn test_parse_anchor_fail_7() {
+    // Only comments can contain arbitrary text.
+    let str = "##[label(see foo, bar!)]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result);
+
+    assert!(parse_result.is_err());
+  }
+
+  #[test]
+  fn test_parse_anchor_fail_8() {
+    let str = "##[comment( )]";
+    let lex_result = lex_tokens(str).unwrap();
+    let parse_result = parse_anchor(lex_result);
+
+    assert!(parse_result.is_err());
+  }
 }
 
 #[cfg(test)]
@@ -324,7 +882,7 @@ mod lexing_tests {
     assert_eq!(&lexed as &[Token], [
       Token::AnchorStart,
       Token::AnchorOp(Op::Label),
-      Token::AnchorOpArg("(Processing)".to_string()),
+      Token::AnchorOpArg("Processing".to_string()),
       Token::AnchorEnd
     ]);
   }
@@ -342,7 +900,7 @@ mod lexing_tests {
     assert_eq!(&lexed as &[Token], [
       Token::AnchorStart,
       Token::AnchorOp(Op::After),
-      Token::AnchorOpArg("(Processing)".to_string()),
+      Token::AnchorOpArg("Processing".to_string()),
       Token::AnchorEnd
     ]);
   }
@@ -360,7 +918,7 @@ mod lexing_tests {
     assert_eq!(&lexed as &[Token], [
       Token::AnchorStart,
       Token::AnchorOp(Op::Before),
-      Token::AnchorOpArg("(Processing)".to_string()),
+      Token::AnchorOpArg("Processing".to_string()),
       Token::AnchorEnd
     ]);
   }
@@ -395,7 +953,7 @@ mod lexing_tests {
     assert_eq!(&lexed as &[Token], [
       Token::AnchorStart,
       Token::AnchorOp(Op::Label),
-      Token::AnchorOpArg("(kebab-case)".to_string()),
+      Token::AnchorOpArg("kebab-case".to_string()),
       Token::AnchorEnd
     ]);
   }
@@ -413,7 +971,43 @@ mod lexing_tests {
     assert_eq!(&lexed as &[Token], [
       Token::AnchorStart,
       Token::AnchorOp(Op::Label),
-      Token::AnchorOpArg("(Has Spaces)".to_string()),
+      Token::AnchorOpArg("Has Spaces".to_string()),
+      Token::AnchorEnd
+    ]);
+  }
+
+  #[test]
+  fn test_lex_9() {
+    let stream = "##[label(foo(bar))]";
+    let lexed = lex_tokens(stream);
+
+    assert!(lexed.is_ok());
+
+    let lexed = Vec::from_iter(lexed.unwrap());
+
+    assert_eq!(lexed.len(), 4);
+    assert_eq!(&lexed as &[Token], [
+      Token::AnchorStart,
+      Token::AnchorOp(Op::Label),
+      Token::AnchorOpArg("foo(bar)".to_string()),
+      Token::AnchorEnd
+    ]);
+  }
+
+  #[test]
+  fn test_lex_10() {
+    let stream = "##[delete(old-section)]";
+    let lexed = lex_tokens(stream);
+
+    assert!(lexed.is_ok());
+
+    let lexed = Vec::from_iter(lexed.unwrap());
+
+    assert_eq!(lexed.len(), 4);
+    assert_eq!(&lexed as &[Token], [
+      Token::AnchorStart,
+      Token::AnchorOp(Op::Delete),
+      Token::AnchorOpArg("old-section".to_string()),
       Token::AnchorEnd
     ]);
   }
@@ -433,4 +1027,12 @@ mod lexing_tests {
 
     assert!(lexed.is_err());
   }
+
+  #[test]
+  fn test_lex_failure_3() {
+    let stream = "##[label(foo(bar)]";
+    let lexed = lex_tokens(stream);
+
+    assert!(lexed.is_err());
+  }
 }
@@ -0,0 +1,286 @@
+//! Saving the structure found by a `Tangler` and loading it back, so that
+//! output can be produced again without reading through the inputs.
+//!
+//! Plans are plain text. After a header line, each line starts with a
+//! keyword saying what it describes; a `block` line is followed by the
+//! lines of the block, as-is, and a `definition` line by the name of the
+//! file the label was defined in.
+
+pub mod errors {
+  error_chain! {
+    errors {
+      CouldNotReadPlan {
+        description("could not read tangle plan")
+        display("could not read tangle plan")
+      }
+
+      MalformedPlan(lineno: usize) {
+        description("malformed tangle plan")
+        display("malformed tangle plan, line {}", lineno)
+      }
+
+      MissingPlanLabel(name: String) {
+        description("tangle plan refers to a nonexistent label")
+        display("tangle plan refers to a nonexistent label: '{}'", name)
+      }
+    }
+  }
+}
+
+use std::io;
+use std::io::{BufRead, Write};
+use std::rc::Rc;
+use std::collections::{BTreeMap, BTreeSet};
+
+use self::errors::*;
+use list::List;
+use super::{Tangler, Tangled, Knot, Block, Anchor, AnchorRef, Comment, Stamp, StampKind, Stats};
+
+const HEADER: &str = "kaiseki-plan 1";
+
+impl Tangler {
+  /// Write out everything needed to produce output later on with
+  /// `Tangler::load_plan()`. Any errors found while reading the inputs
+  /// are *not* saved.
+  pub fn save_plan<W: Write>(&self, mut writer: W) -> io::Result<()> {
+    writeln!(writer, "{}", HEADER)?;
+
+    for file in &self.stats.files {
+      writeln!(writer, "file {}", file)?;
+    }
+    for (op, count) in &self.stats.anchor_counts {
+      writeln!(writer, "count {} {}", count, op)?;
+    }
+    for (label, count) in &self.stats.label_references {
+      writeln!(writer, "reference {} {}", count, label)?;
+    }
+
+    writeln!(writer, "top")?;
+    save_tangled(&mut writer, &self.tangled)?;
+
+    for (name, anchor) in &self.anchors {
+      writeln!(writer, "label {} {}", anchor.indentation, name)?;
+      save_tangled(&mut writer, &anchor.tangled)?;
+    }
+
+    for defined in self.definitions.values() {
+      for (file, key) in defined {
+        writeln!(writer, "definition {}", key)?;
+        writeln!(writer, "{}", file)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Read back a plan written by `Tangler::save_plan()`.
+  pub fn load_plan<R: BufRead>(reader: R) -> Result<Tangler> {
+    let mut lines = PlanLines { reader, lineno: 0 };
+
+    match lines.next()? {
+      Some(ref header) if header == HEADER => {},
+      _ => bail!(ErrorKind::MalformedPlan(lines.lineno))
+    };
+
+    let mut tangled = List::new();
+    let mut anchors = BTreeMap::new();
+    let mut definitions = BTreeMap::new();
+    let mut stats = Stats::default();
+
+    let mut files = BTreeMap::new();  // So that blocks can share filenames.
+    let mut referenced = BTreeSet::new();
+    let mut section: Option<String> = None;  // `None` is the top level.
+
+    while let Some(line) = lines.next()? {
+      let lineno = lines.lineno;
+      let (keyword, rest) = split_word(&line);
+
+      let knot = match keyword {
+        "file" => { stats.files.push(rest.to_string()); None },
+        "count" => {
+          let (count, op) = split_number(lineno, rest)?;
+          stats.anchor_counts.insert(op.to_string(), count);
+          None
+        },
+        "reference" => {
+          let (count, label) = split_number(lineno, rest)?;
+          stats.label_references.insert(label.to_string(), count);
+          None
+        },
+        "top" => { section = None; None },
+        "label" => {
+          let (indentation, name) = split_number(lineno, rest)?;
+          anchors.insert(name.to_string(), Anchor::new(indentation));
+          section = Some(name.to_string());
+          None
+        },
+        "block" | "raw" => {
+          let (block_lineno, rest) = split_number(lineno, rest)?;
+          let (count, file) = split_number(lineno, rest)?;
+
+          let file = files.entry(file.to_string())
+            .or_insert_with(|| Rc::new(file.to_string()))
+            .clone();
+          let mut block = Block::new(file, block_lineno);
+          block.raw = keyword == "raw";
+
+          for _ in 0..count {
+            match lines.next()? {
+              Some(line) => block.lines.push(line),
+              None => bail!(ErrorKind::MalformedPlan(lines.lineno))
+            };
+          }
+
+          Some(Knot::Block(block))
+        },
+        "anchor" => {
+          referenced.insert(rest.to_string());
+          Some(Knot::Anchor(AnchorRef(rest.to_string())))
+        },
+        "duplicate" => {
+          let (indentation, name) = split_number(lineno, rest)?;
+          referenced.insert(name.to_string());
+          Some(Knot::Duplicate(AnchorRef(name.to_string()), indentation))
+        },
+        "comment" => {
+          let (indentation, text) = split_number(lineno, rest)?;
+          Some(Knot::Comment(Comment { indentation, text: text.to_string() }))
+        },
+        "timestamp" | "revision" => {
+          let kind = if keyword == "timestamp" { StampKind::Timestamp } else { StampKind::Revision };
+          match rest.parse() {
+            Ok(indentation) => Some(Knot::Stamp(Stamp { kind, indentation })),
+            Err(_) => bail!(ErrorKind::MalformedPlan(lineno))
+          }
+        },
+        "definition" => {
+          let file = match lines.next()? {
+            Some(file) => file,
+            None => bail!(ErrorKind::MalformedPlan(lines.lineno))
+          };
+          let file = files.entry(file.clone())
+            .or_insert_with(|| Rc::new(file))
+            .clone();
+          // Keys of all but the first definition are qualified with the
+          // file and line, but names can't contain colons.
+          let name = rest.rsplit(':').next().unwrap_or(rest);
+          definitions.entry(name.to_string())
+            .or_insert_with(Vec::new)
+            .push((file, rest.to_string()));
+          None
+        },
+        "gap" => Some(Knot::Gap),
+        _ => bail!(ErrorKind::MalformedPlan(lineno))
+      };
+
+      if let Some(knot) = knot {
+        match section {
+          None => tangled.push_back(knot),
+          Some(ref name) => {
+            let anchor: &mut Anchor = anchors.get_mut(name)
+              .expect("invariant violated: anchor name does not exist");
+            anchor.tangled.push_back(knot);
+          }
+        };
+      }
+    }
+
+    // Output assumes that every label referred to actually exists.
+    if let Some(name) = referenced.into_iter().find(|name| !anchors.contains_key(name)) {
+      bail!(ErrorKind::MissingPlanLabel(name));
+    }
+
+    // Plans saved before definitions were recorded can still have their
+    // labels output, just not qualified with a file.
+    for name in anchors.keys().filter(|key| !key.contains(':')) {
+      definitions.entry(name.clone())
+        .or_insert_with(|| vec![(Rc::new(String::new()), name.clone())]);
+    }
+
+    Ok(Tangler {
+      tangled,
+      anchors,
+      definitions,
+      errors: Vec::new(),
+      stats
+    })
+  }
+}
+
+fn save_tangled<W: Write>(writer: &mut W, tangled: &Tangled) -> io::Result<()> {
+  for knot in tangled.iter() {
+    match *knot {
+      Knot::Block(ref block) => {
+        let keyword = if block.raw { "raw" } else { "block" };
+        writeln!(writer, "{} {} {} {}", keyword, block.lineno, block.lines.len(), block.file)?;
+        for line in &block.lines {
+          writeln!(writer, "{}", line)?;
+        }
+      },
+      Knot::Anchor(AnchorRef(ref name)) => writeln!(writer, "anchor {}", name)?,
+      Knot::Duplicate(AnchorRef(ref name), indentation) => writeln!(writer, "duplicate {} {}", indentation, name)?,
+      Knot::Comment(ref comment) => writeln!(writer, "comment {} {}", comment.indentation, comment.text)?,
+      // The entries are already filled in, so they come out the same as
+      // comments would.
+      Knot::Toc(ref toc) => {
+        for entry in &toc.entries {
+          writeln!(writer, "comment {} {}", toc.indentation, entry)?;
+        }
+      },
+      Knot::Stamp(ref stamp) => match stamp.kind {
+        StampKind::Timestamp => writeln!(writer, "timestamp {}", stamp.indentation)?,
+        StampKind::Revision => writeln!(writer, "revision {}", stamp.indentation)?
+      },
+      Knot::Gap => writeln!(writer, "gap")?
+    };
+  }
+
+  Ok(())
+}
+
+/// Split off the first space-separated word of the line.
+fn split_word(line: &str) -> (&str, &str) {
+  let mut parts = line.splitn(2, ' ');
+  let first = parts.next().unwrap_or("");
+  let rest = parts.next().unwrap_or("");
+
+  (first, rest)
+}
+
+/// Split off a number at the start of the line.
+fn split_number(lineno: usize, line: &str) -> Result<(usize, &str)> {
+  let (number, rest) = split_word(line);
+
+  match number.parse() {
+    Ok(number) => Ok((number, rest)),
+    Err(_) => bail!(ErrorKind::MalformedPlan(lineno))
+  }
+}
+
+/// Reads lines of a plan, keeping track of the line number. Unlike
+/// `BufRead::lines()`, only the newline itself is removed, so that block
+/// lines come back exactly as they were.
+struct PlanLines<R> {
+  reader: R,
+  lineno: usize
+}
+
+impl<R: BufRead> PlanLines<R> {
+  fn next(&mut self) -> Result<Option<String>> {
+    let mut line = String::new();
+
+    let read = self.reader.read_line(&mut line)
+      .chain_err(|| ErrorKind::CouldNotReadPlan)?;
+
+    if read == 0 {
+      return Ok(None);
+    }
+
+    if line.ends_with('\n') {
+      line.pop();
+    }
+    self.lineno += 1;
+
+    Ok(Some(line))
+  }
+}
@@ -0,0 +1,75 @@
+//! Machine-readable summaries of a run, e.g. for build dashboards, and
+//! human-readable diagnostics.
+
+use std::fmt::Display;
+use std::io;
+use std::io::Write;
+
+use Stats;
+use processing_errors::{Error, Severity, severity};
+
+/// Summarize a run as a single JSON object, containing the input files,
+/// the number of output lines, how many anchors used each command, the
+/// labels defined, and how many errors and warnings there were.
+pub fn json_report(stats: &Stats, errors: &[Error]) -> String {
+  let files = stats.files.iter()
+    .map(|file| json_string(file))
+    .collect::<Vec<_>>();
+
+  let anchors = stats.anchor_counts.iter()
+    .map(|(op, count)| format!("{}:{}", json_string(op), count))
+    .collect::<Vec<_>>();
+
+  let labels = stats.label_references.keys()
+    .map(|label| json_string(label))
+    .collect::<Vec<_>>();
+
+  let error_count = errors.iter()
+    .filter(|error| severity(error.kind()) == Severity::Error)
+    .count();
+  let warning_count = errors.len() - error_count;
+
+  format!(
+    "{{\"files\":[{}],\"output_lines\":{},\"anchors\":{{{}}},\"labels\":[{}],\"errors\":{},\"warnings\":{}}}",
+    files.join(","),
+    stats.output_lines,
+    anchors.join(","),
+    labels.join(","),
+    error_count,
+    warning_count
+  )
+}
+
+/// Write each error on its own line, after `prefix` and a colon, as in
+/// `kaiseki: error: ...`.
+pub fn report_errors<E, W>(errors: &[E], prefix: &str, mut stream: W) -> io::Result<()> where
+  E: Display,
+  W: Write
+{
+  for error in errors {
+    writeln!(stream, "{}: {}", prefix, error)?;
+  }
+
+  Ok(())
+}
+
+/// Quote and escape a string as a JSON string literal.
+fn json_string(text: &str) -> String {
+  let mut quoted = String::with_capacity(text.len() + 2);
+
+  quoted.push('"');
+  for c in text.chars() {
+    match c {
+      '"' => quoted.push_str("\\\""),
+      '\\' => quoted.push_str("\\\\"),
+      '\n' => quoted.push_str("\\n"),
+      '\r' => quoted.push_str("\\r"),
+      '\t' => quoted.push_str("\\t"),
+      c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+      c => quoted.push(c)
+    };
+  }
+  quoted.push('"');
+
+  quoted
+}
@@ -0,0 +1,29 @@
+extern crate kaiseki;
+
+use kaiseki::input;
+
+#[test]
+fn test_read_file_list() {
+  let list = "tests/tangling/test1/000-file1\n\ntests/tangling/test1/001-file2\r\n";
+  let files = input::read_file_list(list.as_bytes()).unwrap();
+
+  assert_eq!(files, vec![
+    "tests/tangling/test1/000-file1".to_string(),
+    "tests/tangling/test1/001-file2".to_string()
+  ]);
+}
+
+#[test]
+fn test_files_from_ambiguous_stdin() {
+  use kaiseki::input::errors::ErrorKind;
+
+  let result = input::files_from("-".to_string(), vec!["-".to_string()]);
+
+  match result {
+    Err(ref err) => match *err.kind() {
+      ErrorKind::AmbiguousStdin => {},
+      ref kind => panic!("unexpected error: {}", kind)
+    },
+    Ok(_) => panic!("expected an error")
+  };
+}
@@ -23,3 +23,727 @@ fn test_lines() {
     assert_eq!(&line1 as &str, line2);
   }
 }
+
+#[test]
+fn test_pop_ends_empty() {
+  let mut lines: List<String> = List::new();
+
+  assert_eq!(lines.pop_ends(), (None, None));
+  assert!(lines.is_empty());
+}
+
+#[test]
+fn test_pop_ends_single() {
+  let mut lines: List<String> = List::new();
+  lines.push_back("only".to_string());
+
+  assert_eq!(lines.pop_ends(), (Some("only".to_string()), None));
+  assert!(lines.is_empty());
+}
+
+#[test]
+fn test_pop_ends_multiple() {
+  let mut lines: List<&str> = BODY.lines().collect();
+  let len = lines.len();
+
+  let first = BODY.lines().next();
+  let last = BODY.lines().last();
+
+  assert_eq!(lines.pop_ends(), (first, last));
+  assert_eq!(lines.len(), len - 2);
+  assert_eq!(lines.front(), BODY.lines().nth(1).as_ref());
+  assert_eq!(lines.back(), BODY.lines().rev().nth(1).as_ref());
+}
+
+#[test]
+fn test_rotate_while_edges() {
+  let mut all: List<u32> = vec![2, 4].into_iter().collect();
+  all.rotate_while(|n| n % 2 == 0);
+  assert_eq!(format!("{:?}", all), "List[2, 4]");
+
+  let mut none: List<u32> = vec![1, 2].into_iter().collect();
+  none.rotate_while(|n| n % 2 == 0);
+  assert_eq!(format!("{:?}", none), "List[1, 2]");
+
+  let mut dl: List<u32> = vec![2, 1, 3].into_iter().collect();
+  dl.rotate_while(|n| n % 2 == 0);
+  assert_eq!(dl.iter().rev().collect::<Vec<&u32>>(), vec![&2, &3, &1]);
+  assert_eq!(dl.len(), 3);
+}
+
+#[test]
+fn test_split_at_first_edges() {
+  let mut dl: List<u32> = vec![1, 2].into_iter().collect();
+  assert!(dl.split_at_first(|n| *n > 5).is_none());
+  assert_eq!(dl.len(), 2);
+
+  let tail = dl.split_at_first(|n| *n == 1).unwrap();
+  assert!(dl.is_empty());
+  assert_eq!(dl.front(), None);
+  assert_eq!(dl.back(), None);
+  assert_eq!(tail.len(), 2);
+  assert_eq!(tail.iter().rev().collect::<Vec<&u32>>(), vec![&2, &1]);
+}
+
+#[test]
+fn test_try_from_iter_error() {
+  use std::rc::Rc;
+
+  let element = Rc::new(());
+  let results = vec![Ok(element.clone()), Ok(element.clone()), Err("bad"), Ok(element.clone())];
+
+  let dl: Result<List<Rc<()>>, &str> = List::try_from_iter(results);
+
+  assert_eq!(dl.err(), Some("bad"));
+  assert_eq!(Rc::strong_count(&element), 1);
+}
+
+#[test]
+fn test_iter_circular_empty() {
+  let dl: List<u32> = List::new();
+  assert_eq!(dl.iter_circular(5).count(), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_iter_step_zero() {
+  let dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+  let _ = dl.iter_step(0);
+}
+
+#[test]
+fn test_take_back_links() {
+  let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+
+  let mut taken = dl.take_back(0);
+  assert!(taken.is_empty());
+
+  taken = dl.take_back(1);
+  assert_eq!(taken.iter().rev().collect::<Vec<&u32>>(), vec![&3]);
+  assert_eq!(dl.iter().rev().collect::<Vec<&u32>>(), vec![&2, &1]);
+
+  dl.push_back(4);
+  assert_eq!(format!("{:?}", dl), "List[1, 2, 4]");
+}
+
+#[test]
+fn test_each_chunk_mut_sizes() {
+  let mut dl: List<u32> = vec![1, 2, 3, 4, 5].into_iter().collect();
+  let mut sizes = Vec::new();
+
+  dl.each_chunk_mut(2, |chunk| {
+    sizes.push(chunk.len());
+    *chunk[0] = 0;
+  });
+
+  assert_eq!(sizes, vec![2, 2, 1]);
+  assert_eq!(format!("{:?}", dl), "List[0, 2, 0, 4, 0]");
+}
+
+#[test]
+fn test_iter_mut() {
+  let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+
+  for n in dl.iter_mut() {
+    *n *= 10;
+  }
+
+  assert_eq!(format!("{:?}", dl), "List[10, 20, 30]");
+}
+
+#[test]
+fn test_iter_mut_both_ends() {
+  let mut dl: List<u32> = vec![1, 2, 3, 4].into_iter().collect();
+
+  {
+    let mut iter = dl.iter_mut();
+    assert_eq!(iter.len(), 4);
+
+    *iter.next().unwrap() = 0;
+    *iter.next_back().unwrap() = 5;
+    assert_eq!(iter.len(), 2);
+
+    for n in iter {
+      *n += 100;
+    }
+  }
+
+  assert_eq!(format!("{:?}", dl), "List[0, 102, 103, 5]");
+}
+
+fn drain_case<R>(range: R) -> (Vec<u32>, Vec<u32>) where
+  R: std::ops::RangeBounds<usize>
+{
+  let mut dl: List<u32> = vec![1, 2, 3, 4].into_iter().collect();
+  let drained: Vec<u32> = dl.drain(range).collect();
+
+  // Check the links in both directions.
+  let rest: Vec<u32> = dl.iter().cloned().collect();
+  let mut rest_rev: Vec<u32> = dl.iter().rev().cloned().collect();
+  rest_rev.reverse();
+  assert_eq!(rest, rest_rev);
+  assert_eq!(rest.len(), dl.len());
+
+  (drained, rest)
+}
+
+#[test]
+fn test_drain_ranges() {
+  assert_eq!(drain_case(..2), (vec![1, 2], vec![3, 4]));
+  assert_eq!(drain_case(2..), (vec![3, 4], vec![1, 2]));
+  assert_eq!(drain_case(1..=2), (vec![2, 3], vec![1, 4]));
+  assert_eq!(drain_case(..), (vec![1, 2, 3, 4], vec![]));
+  assert_eq!(drain_case(2..2), (vec![], vec![1, 2, 3, 4]));
+}
+
+#[test]
+fn test_drain_unconsumed() {
+  use std::rc::Rc;
+
+  let element = Rc::new(());
+  let mut dl: List<Rc<()>> = (0..4).map(|_| element.clone()).collect();
+
+  {
+    let mut drain = dl.drain(1..3);
+    drain.next();
+  }
+
+  assert_eq!(dl.len(), 2);
+  assert_eq!(Rc::strong_count(&element), 3);
+}
+
+#[test]
+#[should_panic]
+fn test_drain_out_of_bounds() {
+  let mut dl: List<u32> = vec![1, 2].into_iter().collect();
+  dl.drain(1..3);
+}
+
+#[test]
+fn test_split_off_edges() {
+  let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+
+  let tail = dl.split_off(3);
+  assert!(tail.is_empty());
+  assert_eq!(format!("{:?}", dl), "List[1, 2, 3]");
+
+  let mut tail = dl.split_off(0);
+  assert!(dl.is_empty());
+  assert_eq!(tail.len(), 3);
+  assert_eq!(tail.iter().rev().collect::<Vec<&u32>>(), vec![&3, &2, &1]);
+
+  let rest = tail.split_off(2);
+  assert_eq!(tail.iter().rev().collect::<Vec<&u32>>(), vec![&2, &1]);
+  assert_eq!(rest.iter().rev().collect::<Vec<&u32>>(), vec![&3]);
+}
+
+#[test]
+fn test_split_off_append_back() {
+  let mut dl: List<u32> = vec![1, 2, 3, 4, 5].into_iter().collect();
+
+  let mut tail = dl.split_off(2);
+  assert_eq!((dl.len(), tail.len()), (2, 3));
+
+  dl.append_back(&mut tail);
+  assert!(tail.is_empty());
+  assert_eq!(dl.len(), 5);
+  assert_eq!(format!("{:?}", dl), "List[1, 2, 3, 4, 5]");
+  assert_eq!(dl.iter().rev().collect::<Vec<&u32>>(), vec![&5, &4, &3, &2, &1]);
+}
+
+#[test]
+#[should_panic]
+fn test_split_off_out_of_bounds() {
+  let mut dl: List<u32> = vec![1, 2].into_iter().collect();
+  let _ = dl.split_off(3);
+}
+
+#[test]
+fn test_retain_none() {
+  let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+
+  dl.retain(|_| false);
+  assert!(dl.is_empty());
+  assert_eq!(dl.len(), 0);
+  assert_eq!((dl.front(), dl.back()), (None, None));
+
+  dl.push_back(4);
+  dl.push_front(5);
+  assert_eq!(format!("{:?}", dl), "List[5, 4]");
+  assert_eq!(dl.iter().rev().collect::<Vec<&u32>>(), vec![&4, &5]);
+}
+
+#[test]
+fn test_retain_ends() {
+  let mut dl: List<u32> = vec![1, 2, 3, 4].into_iter().collect();
+
+  dl.retain(|&n| n == 2 || n == 3);
+  assert_eq!(dl.len(), 2);
+  assert_eq!(dl.iter().rev().collect::<Vec<&u32>>(), vec![&3, &2]);
+
+  dl.remove_item(&3);
+  dl.push_back(6);
+  assert_eq!(format!("{:?}", dl), "List[2, 6]");
+}
+
+/// A small linear congruential generator, so that the sorting tests can
+/// check lots of different inputs without needing a source of randomness.
+fn pseudorandom(seed: u64, n: usize, range: u64) -> Vec<u64> {
+  let mut state = seed;
+  (0..n).map(|_| {
+    state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    (state >> 33) % range
+  }).collect()
+}
+
+#[test]
+fn test_sort_matches_vec() {
+  for seed in 0..200 {
+    let mut expected = pseudorandom(seed, (seed % 40) as usize, 10);
+    let mut dl: List<u64> = expected.clone().into_iter().collect();
+
+    dl.sort();
+    expected.sort();
+
+    assert_eq!(dl.len(), expected.len());
+    assert_eq!(dl.iter().cloned().collect::<Vec<u64>>(), expected);
+    assert_eq!(dl.iter().rev().cloned().collect::<Vec<u64>>(), expected.into_iter().rev().collect::<Vec<u64>>());
+  }
+}
+
+#[test]
+fn test_sort_by_stable() {
+  let keys = pseudorandom(7, 100, 5);
+  let pairs: Vec<(u64, usize)> = keys.into_iter().enumerate().map(|(i, key)| (key, i)).collect();
+
+  let mut dl: List<(u64, usize)> = pairs.clone().into_iter().collect();
+  let mut expected = pairs;
+
+  dl.sort_by(|a, b| a.0.cmp(&b.0));
+  expected.sort_by_key(|pair| pair.0);
+
+  assert_eq!(dl.into_iter().collect::<Vec<(u64, usize)>>(), expected);
+}
+
+#[test]
+fn test_clone_independent() {
+  let original: List<String> = vec!["a", "b", "c"].into_iter().map(String::from).collect();
+  let mut copy = original.clone();
+
+  copy.pop_front();
+  copy.push_back("d".to_string());
+  for element in copy.iter_mut() {
+    element.push('!');
+  }
+
+  assert_eq!(original.iter().collect::<Vec<&String>>(), vec!["a", "b", "c"]);
+  assert_eq!(copy.iter().collect::<Vec<&String>>(), vec!["b!", "c!", "d!"]);
+
+  drop(copy);
+  assert_eq!(original.iter().rev().collect::<Vec<&String>>(), vec!["c", "b", "a"]);
+}
+
+#[test]
+fn test_eq() {
+  let empty1: List<u32> = List::new();
+  let empty2: List<u32> = List::new();
+  assert!(empty1 == empty2);
+
+  let dl1: List<u32> = vec![1, 2, 3].into_iter().collect();
+  let dl2: List<u32> = vec![1, 2, 3].into_iter().collect();
+  assert!(dl1 == dl2);
+
+  let different: List<u32> = vec![1, 2, 4].into_iter().collect();
+  assert!(dl1 != different);
+
+  let shorter: List<u32> = vec![1, 2].into_iter().collect();
+  assert!(dl1 != shorter);
+  assert!(shorter != dl1);
+  assert!(empty1 != shorter);
+}
+
+#[test]
+fn test_cursor_seek_insert() {
+  let mut dl: List<u32> = vec![1, 3, 6, 7, 8].into_iter().collect();
+
+  {
+    let mut cursor = dl.cursor_front_mut();
+    assert!(cursor.seek(|n| n % 2 == 0));
+    cursor.insert_before(5);
+    assert_eq!(cursor.current(), Some(&mut 6));
+
+    cursor.move_next();
+    assert!(cursor.seek(|n| n % 2 == 0));
+    assert_eq!(cursor.current(), Some(&mut 8));
+
+    cursor.move_next();
+    assert!(!cursor.seek(|_| true));
+    cursor.insert_before(9);
+  }
+
+  assert_eq!(format!("{:?}", dl), "List[1, 3, 5, 6, 7, 8, 9]");
+  assert_eq!(dl.iter().rev().collect::<Vec<&u32>>(), vec![&9, &8, &7, &6, &5, &3, &1]);
+}
+
+#[test]
+fn test_cursor_ghost() {
+  let mut dl: List<u32> = vec![1, 2].into_iter().collect();
+
+  {
+    let mut cursor = dl.cursor_front_mut();
+    cursor.move_prev();
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.remove_current(), None);
+
+    cursor.insert_before(3);
+    cursor.insert_after(0);
+    assert_eq!(cursor.current(), None);
+
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some(&mut 3));
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&mut 0));
+  }
+
+  assert_eq!(format!("{:?}", dl), "List[0, 1, 2, 3]");
+  assert_eq!(dl.iter().rev().collect::<Vec<&u32>>(), vec![&3, &2, &1, &0]);
+}
+
+#[test]
+fn test_cursor_remove_all() {
+  let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+
+  {
+    let mut cursor = dl.cursor_front_mut();
+    while let Some(n) = cursor.remove_current() {
+      if n == 2 {
+        cursor.insert_before(20);
+      }
+    }
+  }
+
+  assert_eq!(format!("{:?}", dl), "List[20]");
+  assert_eq!((dl.front(), dl.back()), (Some(&20), Some(&20)));
+
+  {
+    let mut cursor = dl.cursor_back_mut();
+    assert_eq!(cursor.remove_current(), Some(20));
+    assert_eq!(cursor.current(), None);
+  }
+
+  assert!(dl.is_empty());
+  assert_eq!((dl.front(), dl.back()), (None, None));
+}
+
+#[test]
+fn test_splice_positions() {
+  let mut dl: List<u32> = vec![2, 4].into_iter().collect();
+
+  let mut other: List<u32> = vec![0, 1].into_iter().collect();
+  dl.splice_before(0, &mut other);
+  assert!(other.is_empty());
+  assert_eq!((other.front(), other.back()), (None, None));
+
+  let mut other: List<u32> = vec![3].into_iter().collect();
+  dl.splice_after(2, &mut other);
+  assert!(other.is_empty());
+
+  let mut other: List<u32> = vec![5, 6].into_iter().collect();
+  dl.splice_after(4, &mut other);
+
+  let mut other: List<u32> = vec![7].into_iter().collect();
+  dl.splice_before(7, &mut other);
+
+  let mut empty: List<u32> = List::new();
+  dl.splice_before(3, &mut empty);
+
+  assert_eq!(dl.len(), 8);
+  assert_eq!(format!("{:?}", dl), "List[0, 1, 2, 3, 4, 5, 6, 7]");
+  assert_eq!(dl.iter().rev().collect::<Vec<&u32>>(), vec![&7, &6, &5, &4, &3, &2, &1, &0]);
+}
+
+#[test]
+fn test_splice_into_empty() {
+  let mut dl: List<u32> = List::new();
+  let mut other: List<u32> = vec![1, 2].into_iter().collect();
+
+  dl.splice_before(0, &mut other);
+
+  assert_eq!(dl.len(), 2);
+  assert_eq!((dl.front(), dl.back()), (Some(&1), Some(&2)));
+  assert!(other.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_splice_after_out_of_bounds() {
+  let mut dl: List<u32> = vec![1, 2].into_iter().collect();
+  let mut other: List<u32> = vec![3].into_iter().collect();
+  dl.splice_after(2, &mut other);
+}
+
+#[test]
+fn test_truncate_lengths() {
+  use std::rc::Rc;
+
+  let element = Rc::new(());
+  let mut dl: List<Rc<()>> = (0..4).map(|_| element.clone()).collect();
+
+  dl.truncate(4);
+  dl.truncate(10);
+  assert_eq!(dl.len(), 4);
+  assert_eq!(Rc::strong_count(&element), 5);
+
+  dl.truncate(3);
+  assert_eq!(dl.len(), 3);
+  assert_eq!(Rc::strong_count(&element), 4);
+  assert_eq!(dl.iter().rev().count(), 3);
+
+  dl.truncate(0);
+  assert!(dl.is_empty());
+  assert_eq!((dl.front(), dl.back()), (None, None));
+  assert_eq!(Rc::strong_count(&element), 1);
+
+  dl.push_back(element.clone());
+  assert_eq!(dl.len(), 1);
+}
+
+#[test]
+fn test_contains_position() {
+  let dl: List<u32> = vec![1, 2, 3, 2].into_iter().collect();
+
+  assert!(dl.contains(&1));
+  assert!(dl.contains(&3));
+  assert!(dl.contains(&2));
+  assert!(!dl.contains(&5));
+
+  assert_eq!(dl.position(|&n| n == 1), Some(0));
+  assert_eq!(dl.position(|&n| n == 2), Some(1));
+  assert_eq!(dl.position(|&n| n > 2), Some(2));
+  assert_eq!(dl.position(|&n| n == 5), None);
+
+  let empty: List<u32> = List::new();
+  assert!(!empty.contains(&1));
+  assert_eq!(empty.position(|_| true), None);
+}
+
+#[test]
+fn test_chunks_exact_sizes() {
+  let dl: List<u32> = vec![1, 2, 3, 4].into_iter().collect();
+
+  let chunks = dl.chunks_exact(2);
+  assert!(chunks.remainder().is_empty());
+  assert_eq!(chunks.collect::<Vec<Vec<&u32>>>(), vec![vec![&1, &2], vec![&3, &4]]);
+
+  let mut chunks = dl.chunks_exact(5);
+  assert_eq!(chunks.remainder(), vec![&1, &2, &3, &4]);
+  assert_eq!(chunks.next(), None);
+
+  let empty: List<u32> = List::new();
+  assert_eq!(empty.chunks_exact(3).count(), 0);
+}
+
+#[test]
+fn test_windows_sizes() {
+  let dl: List<u32> = vec![1, 2, 3, 4, 5].into_iter().collect();
+
+  let windows = dl.windows(2).collect::<Vec<Vec<&u32>>>();
+  assert_eq!(windows.len(), 4);
+  assert_eq!(windows.first(), Some(&vec![&1, &2]));
+  assert_eq!(windows.last(), Some(&vec![&4, &5]));
+
+  assert_eq!(dl.windows(5).collect::<Vec<Vec<&u32>>>(), vec![vec![&1, &2, &3, &4, &5]]);
+  assert_eq!(dl.windows(6).count(), 0);
+
+  let singles = dl.windows(1).map(|window| window[0]).collect::<Vec<&u32>>();
+  assert_eq!(singles, dl.iter().collect::<Vec<&u32>>());
+}
+
+#[test]
+#[should_panic]
+fn test_windows_zero_size() {
+  let dl: List<u32> = vec![1, 2].into_iter().collect();
+  dl.windows(0);
+}
+
+#[test]
+fn list_is_send_sync() {
+  fn assert_send<T: Send>() {}
+  fn assert_sync<T: Sync>() {}
+
+  assert_send::<List<String>>();
+  assert_sync::<List<String>>();
+}
+
+#[test]
+fn test_get_ends() {
+  let mut dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+
+  assert_eq!(dl.get(0), dl.front());
+  assert_eq!(dl.get(2), dl.back());
+  assert_eq!(dl.get(3), None);
+  assert_eq!(dl.get_mut(3), None);
+
+  *dl.get_mut(0).unwrap() = 10;
+  dl[2] += 20;
+  assert_eq!((dl[0], dl[1], dl[2]), (10, 2, 23));
+  assert_eq!(dl.back(), Some(&23));
+
+  let empty: List<u32> = List::new();
+  assert_eq!(empty.get(0), None);
+}
+
+#[test]
+#[should_panic]
+fn test_index_out_of_bounds() {
+  let dl: List<u32> = vec![1, 2].into_iter().collect();
+  let _ = dl[2];
+}
+
+#[test]
+fn test_extend_order() {
+  let mut dl: List<u32> = List::new();
+
+  dl.extend_front(vec![4, 5]);
+  dl.extend_front(Vec::new());
+  dl.extend_front(1..4);
+  dl.extend(vec![6, 7]);
+  dl.extend_back(Some(8));
+
+  assert_eq!(format!("{:?}", dl), "List[1, 2, 3, 4, 5, 6, 7, 8]");
+  assert_eq!(dl.iter().rev().collect::<Vec<&u32>>(), vec![&8, &7, &6, &5, &4, &3, &2, &1]);
+}
+
+#[test]
+fn test_dedup_cases() {
+  fn dedup(elements: Vec<u32>) -> List<u32> {
+    let mut dl: List<u32> = elements.into_iter().collect();
+    dl.dedup();
+    assert_eq!(dl.iter().rev().count(), dl.len());
+    dl
+  }
+
+  assert_eq!(format!("{:?}", dedup(vec![7, 7, 7, 7])), "List[7]");
+  assert_eq!(format!("{:?}", dedup(vec![1, 2, 3])), "List[1, 2, 3]");
+  assert_eq!(format!("{:?}", dedup(vec![1, 2, 1, 2])), "List[1, 2, 1, 2]");
+  assert_eq!(format!("{:?}", dedup(vec![1, 2, 2, 2, 2, 2, 3, 3])), "List[1, 2, 3]");
+  assert!(dedup(Vec::new()).is_empty());
+
+  let dl = dedup(vec![1, 2, 2]);
+  assert_eq!(dl.back(), Some(&2));
+}
+
+#[test]
+fn test_rotate_identities() {
+  let original: List<u32> = vec![1, 2, 3, 4, 5].into_iter().collect();
+
+  for k in 0..6 {
+    let mut dl = original.clone();
+    dl.rotate_left(k);
+    dl.rotate_right(k);
+    assert_eq!(dl, original);
+    assert_eq!(dl.iter().rev().collect::<Vec<&u32>>(), vec![&5, &4, &3, &2, &1]);
+  }
+
+  let mut dl = original.clone();
+  dl.rotate_left(5);
+  assert_eq!(dl, original);
+  dl.rotate_right(5);
+  assert_eq!(dl, original);
+
+  let mut dl = original.clone();
+  dl.rotate_right(2);
+  assert_eq!(format!("{:?}", dl), "List[4, 5, 1, 2, 3]");
+  assert_eq!(dl.back(), Some(&3));
+
+  let mut empty: List<u32> = List::new();
+  empty.rotate_left(0);
+  assert!(empty.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_rotate_out_of_bounds() {
+  let mut dl: List<u32> = vec![1, 2].into_iter().collect();
+  dl.rotate_left(3);
+}
+
+#[test]
+fn test_clear_drops_elements() {
+  use std::rc::Rc;
+
+  let element = Rc::new(());
+  let mut dl: List<Rc<()>> = (0..3).map(|_| element.clone()).collect();
+
+  dl.clear();
+  assert!(dl.is_empty());
+  assert_eq!(dl.front(), None);
+  assert_eq!(dl.back(), None);
+  assert_eq!(Rc::strong_count(&element), 1);
+
+  dl.push_back(element.clone());
+  dl.push_front(element.clone());
+  assert_eq!(dl.len(), 2);
+  assert_eq!(Rc::strong_count(&element), 3);
+}
+
+#[test]
+fn test_drain_filter_partial() {
+  let mut dl: List<u32> = (1..9).collect();
+
+  {
+    let mut drained = dl.drain_filter(|n| n % 3 == 0);
+    assert_eq!(drained.next(), Some(3));
+  }
+  assert_eq!(format!("{:?}", dl), "List[1, 2, 4, 5, 7, 8]");
+
+  let all: Vec<u32> = dl.drain_filter(|_| true).collect();
+  assert_eq!(all, vec![1, 2, 4, 5, 7, 8]);
+  assert!(dl.is_empty());
+  assert_eq!(dl.back(), None);
+
+  dl.push_back(1);
+  assert_eq!(dl.drain_filter(|_| false).count(), 0);
+  assert_eq!(format!("{:?}", dl), "List[1]");
+}
+
+#[test]
+fn test_move_value_positions() {
+  let mut dl: List<u32> = vec![1, 2, 3, 2].into_iter().collect();
+
+  assert!(dl.move_value(&2, 3));
+  assert_eq!(format!("{:?}", dl), "List[1, 3, 2, 2]");
+
+  assert!(dl.move_value(&1, 3));
+  assert_eq!(format!("{:?}", dl), "List[3, 2, 2, 1]");
+  assert_eq!(dl.iter().rev().collect::<Vec<&u32>>(), vec![&1, &2, &2, &3]);
+
+  assert!(dl.move_value(&3, 0));
+  assert_eq!(format!("{:?}", dl), "List[3, 2, 2, 1]");
+
+  let mut empty: List<u32> = List::new();
+  assert!(!empty.move_value(&1, 0));
+}
+
+#[test]
+fn test_split_into_sizes() {
+  let dl: List<u32> = (1..8).collect();
+  let parts = dl.split_into(3);
+  assert_eq!(parts.iter().map(|part| part.len()).collect::<Vec<usize>>(), vec![3, 2, 2]);
+  assert_eq!(parts[2].back(), Some(&7));
+
+  let dl: List<u32> = vec![1, 2].into_iter().collect();
+  let parts = dl.split_into(4);
+  assert_eq!(format!("{:?}", parts), "[List[1], List[2], List[], List[]]");
+
+  let empty: List<u32> = List::new();
+  assert_eq!(empty.split_into(1), vec![List::new()]);
+}
+
+#[test]
+#[should_panic]
+fn test_split_into_zero() {
+  let dl: List<u32> = vec![1, 2].into_iter().collect();
+  dl.split_into(0);
+}
@@ -0,0 +1,72 @@
+extern crate kaiseki;
+
+use kaiseki::input;
+use kaiseki::Tangler;
+
+fn output_options() -> kaiseki::OutputOptions {
+  kaiseki::OutputOptions {
+    comment: Some("//".to_string()),
+    ..Default::default()
+  }
+}
+
+#[test]
+fn test_plan_round_trip() {
+  let files = ["tests/tangling/test3/000-file1", "tests/tangling/test3/001-file2"];
+  let files: Vec<String> = files.iter().map(|str| str.to_string()).collect();
+
+  let (expected, _, expected_stats) = {
+    let files = input::open_files(files.clone()).unwrap();
+    kaiseki::tangle_output_with_stats(files, output_options())
+  };
+
+  let mut plan = Vec::new();
+  let files = input::open_files(files).unwrap();
+  Tangler::new(files, &output_options()).save_plan(&mut plan).unwrap();
+
+  let tangler = Tangler::load_plan(&plan[..]).unwrap();
+  let (output, errors, stats) = tangler.tangle_output(output_options());
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(output, expected);
+  assert_eq!(stats.files, expected_stats.files);
+  assert_eq!(stats.label_references, expected_stats.label_references);
+}
+
+#[test]
+fn test_plan_qualified_label() {
+  let files = [
+    "tests/tangling/test22/000-file1",
+    "tests/tangling/test22/001-file2",
+    "tests/tangling/test22/002-file3"
+  ];
+  let files: Vec<String> = files.iter().map(|str| str.to_string()).collect();
+
+  let mut plan = Vec::new();
+  let files = input::open_files(files).unwrap();
+  Tangler::new(files, &Default::default()).save_plan(&mut plan).unwrap();
+
+  let (output, errors, _) = Tangler::load_plan(&plan[..]).unwrap()
+    .tangle_label(Default::default(), "000-file1:Section");
+  assert_eq!(errors.len(), 0);
+  assert_eq!(output, vec!["fn first() {}".to_string()]);
+
+  let (output, _, _) = Tangler::load_plan(&plan[..]).unwrap()
+    .tangle_label(Default::default(), "Section");
+  assert_eq!(output, vec!["fn latest() {}".to_string()]);
+}
+
+#[test]
+fn test_plan_missing_label() {
+  use kaiseki::plan::errors::ErrorKind;
+
+  let plan = "kaiseki-plan 1\ntop\nanchor Nowhere\n";
+
+  match Tangler::load_plan(plan.as_bytes()) {
+    Err(ref err) => match *err.kind() {
+      ErrorKind::MissingPlanLabel(ref name) => assert_eq!(name, "Nowhere"),
+      ref kind => panic!("unexpected error: {}", kind)
+    },
+    Ok(_) => panic!("expected an error")
+  };
+}
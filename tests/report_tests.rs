@@ -0,0 +1,51 @@
+extern crate kaiseki;
+
+use kaiseki::input;
+use kaiseki::report::json_report;
+
+#[test]
+fn test_json_report() {
+  let files = ["tests/tangling/test3/000-file1", "tests/tangling/test3/001-file2"];
+  let files: Vec<String> = files.iter().map(|str| str.to_string()).collect();
+  let files = input::open_files(files).unwrap();
+
+  let (_, errors, stats) = kaiseki::tangle_output_with_stats(files, Default::default());
+  let report = json_report(&stats, &errors);
+
+  assert!(report.starts_with('{') && report.ends_with('}'));
+  assert!(report.contains(r#""files":["tests/tangling/test3/000-file1","tests/tangling/test3/001-file2"]"#));
+  assert!(report.contains(r#""output_lines":8"#));
+  assert!(report.contains(r#""anchors":{"after":1,"before":1,"label":2}"#));
+  assert!(report.contains(r#""labels":["Main","Unused"]"#));
+  assert!(report.contains(r#""errors":0,"warnings":0"#));
+}
+
+#[test]
+fn test_json_report_warnings() {
+  let files = vec!["tests/tangling/test5/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let (_, errors, stats) = kaiseki::tangle_output_with_stats(files, Default::default());
+  let report = json_report(&stats, &errors);
+
+  assert!(report.contains(r#""labels":[]"#));
+  assert!(report.contains(r#""errors":0,"warnings":2"#));
+}
+
+#[test]
+fn test_report_errors_prefix() {
+  use kaiseki::report::report_errors;
+
+  let files = vec!["tests/tangling/test5/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let (_, errors) = kaiseki::tangle_output(files, Default::default()).into();
+  assert!(errors.len() >= 2);
+
+  let mut written = Vec::new();
+  report_errors(&errors[..2], "tangle", &mut written).unwrap();
+  let written = String::from_utf8(written).unwrap();
+
+  let expected = format!("tangle: {}\ntangle: {}\n", errors[0], errors[1]);
+  assert_eq!(written, expected);
+}
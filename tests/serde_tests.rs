@@ -0,0 +1,31 @@
+#![cfg(feature = "serde")]
+
+extern crate kaiseki;
+extern crate serde_json;
+
+use kaiseki::list::List;
+
+#[test]
+fn test_serialize_like_vec() {
+  let dl: List<u32> = vec![1, 2, 3].into_iter().collect();
+
+  assert_eq!(serde_json::to_string(&dl).unwrap(), "[1,2,3]");
+  assert_eq!(serde_json::to_string(&List::<u32>::new()).unwrap(), "[]");
+}
+
+#[test]
+fn test_round_trip() {
+  let dl: List<String> = vec!["fn main() {", "}"].into_iter().map(String::from).collect();
+
+  let json = serde_json::to_string(&dl).unwrap();
+  let back: List<String> = serde_json::from_str(&json).unwrap();
+
+  assert!(back == dl);
+  assert_eq!(back.iter().rev().collect::<Vec<&String>>(), vec!["}", "fn main() {"]);
+}
+
+#[test]
+fn test_deserialize_not_a_sequence() {
+  let result: Result<List<u32>, _> = serde_json::from_str("{\"a\": 1}");
+  assert!(result.is_err());
+}
@@ -11,10 +11,11 @@ fn test_test1() {
   let files = input::open_files(files).unwrap();
 
   let output_options = kaiseki::OutputOptions {
-    comment: None
+    comment: None,
+    ..Default::default()
   };
 
-  let (output, errors) = kaiseki::tangle_output(files, output_options);
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
 
   assert_eq!(errors.len(), 0);
   for (line1, line2) in OUTPUT.lines().zip(output) {
@@ -43,13 +44,1044 @@ fn test_test2() {
   let files = input::open_files(files).unwrap();
 
   let output_options = kaiseki::OutputOptions {
-    comment: None
+    comment: None,
+    ..Default::default()
   };
 
-  let (output, errors) = kaiseki::tangle_output(files, output_options);
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
 
   assert_eq!(errors.len(), 0);
   for (line1, line2) in OUTPUT.lines().zip(output) {
     assert_eq!(line1, &line2 as &str);
   }
 }
+
+#[test]
+fn test_label_references() {
+  static OUTPUT: &'static str = include_str!("tangling/test3/output");
+
+  let files = ["tests/tangling/test3/000-file1", "tests/tangling/test3/001-file2"];
+  let files: Vec<String> = files.iter().map(|str| str.to_string()).collect();
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    comment: None,
+    ..Default::default()
+  };
+
+  let (output, errors, stats) = kaiseki::tangle_output_with_stats(files, output_options);
+
+  assert_eq!(errors.len(), 0);
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+
+  assert_eq!(stats.label_references.len(), 2);
+  assert_eq!(stats.label_references["Main"], 2);
+  assert_eq!(stats.label_references["Unused"], 0);
+}
+
+#[test]
+fn test_source_markers() {
+  static OUTPUT: &'static str = include_str!("tangling/test4/output");
+
+  let files = vec!["tests/tangling/test4/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    comment: Some("//".to_string()),
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_allowed_errors() {
+  use kaiseki::processing_errors::{ErrorKind, filter_allowed};
+
+  let files = vec!["tests/tangling/test5/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    comment: None,
+    ..Default::default()
+  };
+
+  let (_, errors) = kaiseki::tangle_output(files, output_options).into();
+  assert_eq!(errors.len(), 2);
+
+  let errors = filter_allowed(errors, &["missing-tag".to_string()]);
+  assert_eq!(errors.len(), 1);
+  match *errors[0].kind() {
+    ErrorKind::MalformedAnchor(..) => (),
+    _ => panic!("expected a malformed anchor error")
+  };
+}
+
+#[test]
+fn test_final_anchor_without_newline() {
+  static OUTPUT: &'static str = include_str!("tangling/test6/output");
+
+  let tangle = |file: &str| {
+    let files = input::open_files(vec![file.to_string()]).unwrap();
+
+    let output_options = kaiseki::OutputOptions {
+      comment: None,
+      ..Default::default()
+    };
+
+    kaiseki::tangle_output(files, output_options).into()
+  };
+
+  let (output1, errors1) = tangle("tests/tangling/test6/trailing-newline");
+  let (output2, errors2) = tangle("tests/tangling/test6/no-trailing-newline");
+
+  assert_eq!(errors1.len(), 0);
+  assert_eq!(errors2.len(), 0);
+  assert_eq!(output1, output2);
+  assert_eq!(OUTPUT.lines().count(), output2.len());
+  for (line1, line2) in OUTPUT.lines().zip(output2) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_block_separator() {
+  static OUTPUT: &'static str = include_str!("tangling/test7/output");
+
+  let files = vec!["tests/tangling/test7/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    comment: None,
+    block_separator: Some("---".to_string()),
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_mixed_anchor_syntaxes() {
+  use kaiseki::AnchorSyntax;
+
+  static OUTPUT: &'static str = include_str!("tangling/test8/output");
+
+  let files = vec!["tests/tangling/test8/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    anchor_syntaxes: vec![AnchorSyntax::Native, AnchorSyntax::Noweb],
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_reserved_names() {
+  use kaiseki::processing_errors::ErrorKind;
+
+  let files = vec!["tests/tangling/test9/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    reserved_names: vec!["main".to_string()].into_iter().collect(),
+    ..Default::default()
+  };
+
+  let (_, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 1);
+  match *errors[0].kind() {
+    ErrorKind::ReservedName(_, lineno, ref tag) => {
+      assert_eq!(lineno, 2);
+      assert_eq!(tag, "main");
+    },
+    _ => panic!("expected a reserved name warning")
+  };
+}
+
+#[test]
+fn test_tab_size() {
+  let tangle = |columns: usize| {
+    let files = vec!["tests/tangling/test10/input".to_string()];
+    let files = input::open_files(files).unwrap();
+
+    let output_options = kaiseki::OutputOptions {
+      tab_size: columns,
+      ..Default::default()
+    };
+
+    kaiseki::tangle_output(files, output_options).into()
+  };
+
+  let (output, errors) = tangle(4);
+  assert_eq!(errors.len(), 0);
+  assert_eq!(&output[1] as &str, "    println!(\"Hello world!\");");
+
+  let (output, errors) = tangle(8);
+  assert_eq!(errors.len(), 0);
+  assert_eq!(&output[1] as &str, "        println!(\"Hello world!\");");
+}
+
+#[test]
+fn test_comment_anchor() {
+  static OUTPUT: &'static str = include_str!("tangling/test11/output");
+  static OUTPUT_COMMENTED: &'static str = include_str!("tangling/test11/output-commented");
+
+  let tangle = |leader: Option<String>| {
+    let files = vec!["tests/tangling/test11/input".to_string()];
+    let files = input::open_files(files).unwrap();
+
+    let output_options = kaiseki::OutputOptions {
+      comment: leader,
+      ..Default::default()
+    };
+
+    kaiseki::tangle_output(files, output_options).into()
+  };
+
+  for &(expected, ref comment) in &[(OUTPUT, None), (OUTPUT_COMMENTED, Some("//".to_string()))] {
+    let (output, errors) = tangle(comment.clone());
+
+    assert_eq!(errors.len(), 0);
+    assert_eq!(expected.lines().count(), output.len());
+    for (line1, line2) in expected.lines().zip(output) {
+      assert_eq!(line1, &line2 as &str);
+    }
+  }
+}
+
+#[test]
+fn test_tangle_label() {
+  static OUTPUT: &'static str = include_str!("tangling/test12/output");
+
+  let files = vec!["tests/tangling/test12/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let (output, errors) = kaiseki::tangle_label(files, Default::default(), "body");
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_tangle_missing_label() {
+  use kaiseki::processing_errors::ErrorKind;
+
+  let files = vec!["tests/tangling/test12/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let (output, errors) = kaiseki::tangle_label(files, Default::default(), "nonexistent");
+
+  assert_eq!(output.len(), 0);
+  assert_eq!(errors.len(), 1);
+  match *errors[0].kind() {
+    ErrorKind::MissingLabel(ref tag) => assert_eq!(tag, "nonexistent"),
+    ref kind => panic!("unexpected error: {}", kind)
+  };
+}
+
+#[test]
+fn test_keep_source_line_gaps() {
+  static INPUT: &'static str = include_str!("tangling/test13/input");
+  static OUTPUT: &'static str = include_str!("tangling/test13/output");
+
+  let files = vec!["tests/tangling/test13/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    keep_source_line_gaps: true,
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(INPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_max_line_length() {
+  use kaiseki::processing_errors::ErrorKind;
+
+  let files = vec!["tests/tangling/test14/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    max_line_length: Some(20),
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(output.len(), 3);
+  assert_eq!(errors.len(), 1);
+  match *errors[0].kind() {
+    ErrorKind::LineTooLong(lineno, length) => assert_eq!((lineno, length), (2, 39)),
+    ref kind => panic!("unexpected error: {}", kind)
+  };
+}
+
+#[test]
+fn test_top_level_around_labels() {
+  static OUTPUT: &'static str = include_str!("tangling/test15/output");
+
+  let files = vec!["tests/tangling/test15/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let (output, errors) = kaiseki::tangle_output(files, Default::default()).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_embed_diagnostics() {
+  static OUTPUT: &'static str = include_str!("tangling/test16/output");
+
+  let files = vec!["tests/tangling/test16/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    comment: Some("//".to_string()),
+    embed_diagnostics: true,
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 1);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_duplicate_anchor() {
+  static OUTPUT: &'static str = include_str!("tangling/test17/output");
+
+  let files = vec!["tests/tangling/test17/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let (output, errors) = kaiseki::tangle_output(files, Default::default()).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_recursive_duplicate() {
+  use kaiseki::processing_errors::ErrorKind;
+
+  let files = vec!["tests/tangling/test17/recursive".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let (output, errors) = kaiseki::tangle_output(files, Default::default()).into();
+
+  assert_eq!(output, vec!["again:".to_string()]);
+  assert_eq!(errors.len(), 1);
+  match *errors[0].kind() {
+    ErrorKind::RecursiveLabel(ref tag) => assert_eq!(tag, "loop"),
+    ref kind => panic!("unexpected error: {}", kind)
+  };
+}
+
+#[test]
+fn test_dump_structure() {
+  let files = ["tests/tangling/test3/000-file1", "tests/tangling/test3/001-file2"];
+  let files: Vec<String> = files.iter().map(|str| str.to_string()).collect();
+  let files = input::open_files(files).unwrap();
+
+  let dump = kaiseki::Tangler::new(files, &Default::default()).dump_structure();
+
+  assert_eq!(dump[0], "top level:");
+  assert!(dump.contains(&"  block 'tests/tangling/test3/000-file1', line 1 (1 line)".to_string()));
+  assert!(dump.contains(&"  label 'Main'".to_string()));
+  assert!(dump.contains(&"  label 'Unused'".to_string()));
+  assert!(dump.contains(&"label 'Main', indentation 2:".to_string()));
+  assert!(dump.contains(&"label 'Unused', indentation 0:".to_string()));
+  assert!(dump.contains(&"  block 'tests/tangling/test3/001-file2', line 5 (2 lines)".to_string()));
+}
+
+#[test]
+fn test_aliases() {
+  static OUTPUT: &'static str = include_str!("tangling/test18/output");
+
+  let files = vec!["tests/tangling/test18/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    aliases: vec![("Old Body".to_string(), "Body".to_string())].into_iter().collect(),
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_sort_inputs() {
+  static OUTPUT: &'static str = include_str!("tangling/test1/output");
+
+  let tangle = |files: &[&str]| {
+    let files: Vec<String> = files.iter().map(|str| str.to_string()).collect();
+    let files = input::open_files(files).unwrap();
+
+    let output_options = kaiseki::OutputOptions {
+      sort_inputs: true,
+      ..Default::default()
+    };
+
+    kaiseki::tangle_output(files, output_options).into()
+  };
+
+  let (output1, errors1) = tangle(&["tests/tangling/test1/000-file1", "tests/tangling/test1/001-file2"]);
+  let (output2, errors2) = tangle(&["tests/tangling/test1/001-file2", "tests/tangling/test1/000-file1"]);
+
+  assert_eq!(errors1.len(), 0);
+  assert_eq!(errors2.len(), 0);
+  assert_eq!(output1, output2);
+  for (line1, line2) in OUTPUT.lines().zip(output2) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_strip_prefix() {
+  static OUTPUT: &'static str = include_str!("tangling/test19/output");
+
+  let files = vec!["tests/tangling/test19/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    strip_prefix: Some("/// ".to_string()),
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_strip_prefix_anchor_indentation() {
+  static OUTPUT: &'static str = include_str!("tangling/test31/output");
+
+  let files = vec!["tests/tangling/test31/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    strip_prefix: Some("/// ".to_string()),
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_replace_anchor() {
+  static OUTPUT: &'static str = include_str!("tangling/test20/output");
+
+  let files = ["tests/tangling/test20/000-file1", "tests/tangling/test20/001-file2"];
+  let files: Vec<String> = files.iter().map(|str| str.to_string()).collect();
+  let files = input::open_files(files).unwrap();
+
+  let (output, errors) = kaiseki::tangle_output(files, Default::default()).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output.iter()) {
+    assert_eq!(line1, line2 as &str);
+  }
+  assert!(!output.iter().any(|line| line.contains("hello") || line.contains("world")));
+}
+
+#[test]
+fn test_delete_anchor() {
+  use kaiseki::processing_errors::ErrorKind;
+
+  static OUTPUT: &'static str = include_str!("tangling/test21/output");
+
+  let files = ["tests/tangling/test21/000-file1", "tests/tangling/test21/001-file2"];
+  let files: Vec<String> = files.iter().map(|str| str.to_string()).collect();
+  let files = input::open_files(files).unwrap();
+
+  let (output, errors) = kaiseki::tangle_output(files, Default::default()).into();
+
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+
+  assert_eq!(errors.len(), 1);
+  match *errors[0].kind() {
+    ErrorKind::MissingTag(_, lineno, ref tag) => assert_eq!((lineno, tag as &str), (2, "Nonexistent")),
+    ref kind => panic!("unexpected error: {}", kind)
+  };
+}
+
+#[test]
+fn test_delete_after_placed_lines() {
+  static OUTPUT: &'static str = include_str!("tangling/test32/output");
+
+  let files = vec!["tests/tangling/test32/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let (output, errors) = kaiseki::tangle_output(files, Default::default()).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_tangle_qualified_label() {
+  let files = [
+    "tests/tangling/test22/000-file1",
+    "tests/tangling/test22/001-file2",
+    "tests/tangling/test22/002-file3"
+  ];
+  let files: Vec<String> = files.iter().map(|str| str.to_string()).collect();
+
+  // Without a file, the latest definition is used, as with references.
+  let (output, _) = kaiseki::tangle_label(input::open_files(files.clone()).unwrap(), Default::default(), "Section");
+  assert_eq!(output, vec!["fn latest() {}".to_string()]);
+
+  let (output, _) = kaiseki::tangle_label(input::open_files(files).unwrap(), Default::default(), "000-file1:Section");
+  assert_eq!(output, vec!["fn first() {}".to_string()]);
+}
+
+#[test]
+fn test_qualified_label_reference() {
+  use kaiseki::processing_errors::ErrorKind;
+
+  static OUTPUT: &'static str = include_str!("tangling/test22/output");
+
+  let files = [
+    "tests/tangling/test22/000-file1",
+    "tests/tangling/test22/001-file2",
+    "tests/tangling/test22/002-file3"
+  ];
+  let files: Vec<String> = files.iter().map(|str| str.to_string()).collect();
+  let files = input::open_files(files).unwrap();
+
+  let (output, errors) = kaiseki::tangle_output(files, Default::default()).into();
+
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+
+  assert_eq!(errors.len(), 1);
+  match *errors[0].kind() {
+    ErrorKind::MissingTag(_, lineno, ref tag) => assert_eq!((lineno, tag as &str), (5, "003-file4:Section")),
+    ref kind => panic!("unexpected error: {}", kind)
+  };
+}
+
+#[test]
+fn test_header_min_block_lines() {
+  static OUTPUT: &'static str = include_str!("tangling/test23/output");
+
+  let files = vec!["tests/tangling/test23/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    comment: Some("//".to_string()),
+    header_min_block_lines: 3,
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_normalize_trailing() {
+  static OUTPUT: &'static str = include_str!("tangling/test24/output");
+
+  let files = vec!["tests/tangling/test24/input".to_string()];
+
+  let (output, _) = kaiseki::tangle_output(input::open_files(files.clone()).unwrap(), Default::default()).into();
+  assert_eq!(output.len(), 6);
+  assert_eq!(&output[3..], &["", "  ", ""]);
+
+  let output_options = kaiseki::OutputOptions {
+    normalize_trailing: true,
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(input::open_files(files).unwrap(), output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_block_indentation_first_line() {
+  use kaiseki::BlockIndentation;
+
+  static OUTPUT: &'static str = include_str!("tangling/test25/output");
+
+  let files = vec!["tests/tangling/test25/input".to_string()];
+
+  let output_options = kaiseki::OutputOptions {
+    block_indentation: BlockIndentation::RelativeToFirstLine,
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(input::open_files(files.clone()).unwrap(), output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+
+  // The unindented line keeps the minimum at zero, so nothing moves.
+  let output_options = kaiseki::OutputOptions {
+    block_indentation: BlockIndentation::RelativeToMinimum,
+    ..Default::default()
+  };
+
+  let (output, _) = kaiseki::tangle_output(input::open_files(files.clone()).unwrap(), output_options).into();
+  let (as_written, _) = kaiseki::tangle_output(input::open_files(files).unwrap(), Default::default()).into();
+
+  assert_eq!(output, as_written);
+  assert_eq!(output[1], "    let total = values");
+  assert_eq!(output[4], "  // not indented");
+}
+
+#[test]
+fn test_output_matches_file() {
+  let files = [
+    "tests/tangling/test22/000-file1",
+    "tests/tangling/test22/001-file2",
+    "tests/tangling/test22/002-file3"
+  ];
+  let files: Vec<String> = files.iter().map(|str| str.to_string()).collect();
+  let files = input::open_files(files).unwrap();
+
+  let (mut output, _) = kaiseki::tangle_output(files, Default::default()).into();
+
+  assert!(kaiseki::output_matches_file(&output, "tests/tangling/test22/output").unwrap());
+  assert!(!kaiseki::output_matches_file(&output, "tests/tangling/test21/output").unwrap());
+  assert!(!kaiseki::output_matches_file(&output, "tests/tangling/test22/nonexistent").unwrap());
+
+  output.push(String::new());
+  assert!(!kaiseki::output_matches_file(&output, "tests/tangling/test22/output").unwrap());
+
+  output.truncate(output.len() - 2);
+  assert!(!kaiseki::output_matches_file(&output, "tests/tangling/test22/output").unwrap());
+}
+
+#[test]
+fn test_output_matches_file_unreadable() {
+  let output = vec!["fn main() {}".to_string()];
+
+  // Anything besides the file not existing is an error.
+  assert!(kaiseki::output_matches_file(&output, "tests/tangling").is_err());
+  assert!(!kaiseki::output_matches_file(&[], "tests/tangling/test22/output").unwrap());
+}
+
+#[test]
+fn test_toc() {
+  static OUTPUT: &'static str = include_str!("tangling/test26/output");
+
+  let files = vec!["tests/tangling/test26/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    comment: Some("//".to_string()),
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_raw_region() {
+  static OUTPUT: &'static str = include_str!("tangling/test27/output");
+
+  let files = vec!["tests/tangling/test27/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    strip_prefix: Some("text ".to_string()),
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_multiple_before_order() {
+  static OUTPUT: &'static str = include_str!("tangling/test28/output");
+
+  let files = vec!["tests/tangling/test28/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let (output, errors) = kaiseki::tangle_output(files, Default::default()).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_before_in_order() {
+  static OUTPUT: &'static str = include_str!("tangling/test28/output-in-order");
+
+  let files = vec!["tests/tangling/test28/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    before_in_order: true,
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(OUTPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_tangle_to_writer() {
+  static OUTPUT: &'static str = include_str!("tangling/test15/output");
+
+  let files = vec!["tests/tangling/test15/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let mut written = Vec::new();
+  let (count, errors) = kaiseki::tangle_to_writer(files, Default::default(), &mut written);
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(count, OUTPUT.lines().count());
+  assert_eq!(String::from_utf8(written).unwrap(), OUTPUT);
+}
+
+#[test]
+fn test_tangle_to_failing_writer() {
+  use kaiseki::processing_errors::ErrorKind;
+  use std::io;
+
+  struct Full;
+
+  impl io::Write for Full {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+      Err(io::Error::other("disk full"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      Ok(())
+    }
+  }
+
+  let files = vec!["tests/tangling/test15/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let (count, errors) = kaiseki::tangle_to_writer(files, Default::default(), Full);
+
+  assert_eq!(count, 0);
+  assert_eq!(errors.len(), 1);
+  match *errors[0].kind() {
+    ErrorKind::WriteFailed(ref reason) => assert_eq!(reason, "disk full"),
+    _ => panic!("expected a write failure")
+  };
+}
+
+#[test]
+fn test_skip_leading_shebang() {
+  static OUTPUT: &'static str = include_str!("tangling/test29/output");
+  static OUTPUT_KEEP_FIRST: &'static str = include_str!("tangling/test29/output-keep-first");
+
+  fn tangle(keep_first_shebang: bool) -> Vec<String> {
+    let files = vec![
+      "tests/tangling/test29/000-file1".to_string(),
+      "tests/tangling/test29/001-file2".to_string()
+    ];
+    let files = input::open_files(files).unwrap();
+
+    let output_options = kaiseki::OutputOptions {
+      skip_leading_shebang: true,
+      keep_first_shebang,
+      ..Default::default()
+    };
+
+    let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+    assert_eq!(errors.len(), 0);
+    output
+  }
+
+  for (expected, keep_first_shebang) in &[(OUTPUT, false), (OUTPUT_KEEP_FIRST, true)] {
+    let output = tangle(*keep_first_shebang);
+
+    assert_eq!(expected.lines().count(), output.len());
+    for (line1, line2) in expected.lines().zip(output) {
+      assert_eq!(line1, &line2 as &str);
+    }
+  }
+}
+
+#[test]
+fn test_tangle_str() {
+  let input = "fn main() {\n  // ##[label(Body)]\n}\n// ##[after(Body)]\nrun();\n";
+
+  let (output, errors) = kaiseki::tangle_str(input, Default::default());
+  assert_eq!(errors.len(), 0);
+  assert_eq!(output, "fn main() {\n  run();\n}");
+
+  let output_options = kaiseki::OutputOptions {
+    final_newline: true,
+    ..Default::default()
+  };
+  let (output, _) = kaiseki::tangle_str(input, output_options);
+  assert_eq!(output, "fn main() {\n  run();\n}\n");
+
+  let (output, errors) = kaiseki::tangle_str("// ##[after(Missing)]\n", Default::default());
+  assert_eq!(output, "");
+  assert_eq!(errors.len(), 1);
+  assert!(errors[0].to_string().contains("'<string>', line 1"));
+}
+
+#[test]
+fn test_comment_with_brackets() {
+  let input = "fn main() {}\n// ##[comment(see [1], then f(x))]\n";
+
+  let (output, errors) = kaiseki::tangle_str(input, Default::default());
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(output, "fn main() {}\nsee [1], then f(x)");
+}
+
+#[test]
+fn test_tangle_output_methods() {
+  static OUTPUT: &'static str = include_str!("tangling/test15/output");
+
+  let files = vec!["tests/tangling/test15/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output = kaiseki::tangle_output(files, Default::default());
+
+  assert!(!output.has_errors());
+  assert_eq!(output.line_count(), OUTPUT.lines().count());
+
+  let mut written = Vec::new();
+  output.write_to(&mut written).unwrap();
+  assert_eq!(String::from_utf8(written).unwrap(), OUTPUT);
+  assert_eq!(output.into_string(), OUTPUT);
+
+  let files = input::open_files(vec!["tests/tangling/test15/input".to_string()]).unwrap();
+  let output_options = kaiseki::OutputOptions {
+    final_newline: true,
+    ..Default::default()
+  };
+  let (output, _) = kaiseki::tangle_to_string(files, output_options);
+  assert_eq!(output, OUTPUT);
+}
+
+#[test]
+fn test_tangle_output_has_errors() {
+  use kaiseki::processing_errors::ErrorKind;
+
+  // Warnings alone don't count as errors.
+  let file = input::File {
+    name: "<string>".to_string(),
+    contents: Box::new(::std::io::Cursor::new(b"// ##[after(Missing)]\n".to_vec()))
+  };
+  let output = kaiseki::tangle_output(vec![file], Default::default());
+  assert_eq!(output.errors.len(), 1);
+  assert!(!output.has_errors());
+
+  let output = kaiseki::TangleOutput {
+    lines: Vec::new(),
+    errors: vec![ErrorKind::MissingLabel("Missing".to_string()).into()]
+  };
+  assert!(output.has_errors());
+}
+
+#[test]
+fn test_timestamp() {
+  let input = "fn main() {}\n  // ##[timestamp]\n";
+  let output_options = kaiseki::OutputOptions {
+    comment: Some("//".to_string()),
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_str(input, output_options);
+  assert_eq!(errors.len(), 0);
+
+  let mut lines = output.lines();
+  assert_eq!(lines.next(), Some("// '<string>', line 1"));
+  assert_eq!(lines.next(), Some("fn main() {}"));
+
+  let stamp = lines.next().unwrap();
+  assert!(stamp.starts_with("  // "));
+  assert_eq!(lines.next(), None);
+
+  // e.g. 2017-03-01T12:30:00Z
+  let stamp = &stamp["  // ".len()..];
+  let fields: Vec<u32> = stamp.trim_end_matches('Z')
+    .split(&['-', 'T', ':'][..])
+    .map(|field| field.parse().unwrap())
+    .collect();
+  assert_eq!(stamp.len(), 20);
+  assert_eq!(fields.len(), 6);
+  assert!(fields[0] >= 2017);
+  assert!(fields[1] >= 1 && fields[1] <= 12);
+  assert!(fields[2] >= 1 && fields[2] <= 31);
+  assert!(fields[3] < 24 && fields[4] < 60 && fields[5] < 60);
+}
+
+#[test]
+fn test_unknown_revision_reported_once() {
+  use kaiseki::processing_errors::ErrorKind;
+
+  // Nothing else looks at git, so pointing it somewhere without a
+  // repository doesn't affect other tests.
+  std::env::set_var("GIT_DIR", "tests/tangling/no-such-repository");
+
+  let input = "// ##[revision]\n// ##[revision]\n";
+  let output_options = kaiseki::OutputOptions {
+    comment: Some("//".to_string()),
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_str(input, output_options);
+
+  assert_eq!(output, "// unknown\n// unknown");
+  assert_eq!(errors.len(), 1);
+  match *errors[0].kind() {
+    ErrorKind::UnknownRevision(_) => {},
+    ref kind => panic!("unexpected error: {}", kind)
+  };
+}
+
+#[test]
+fn test_raw_region_keeps_line_gaps() {
+  static INPUT: &'static str = include_str!("tangling/test30/input");
+  static OUTPUT: &'static str = include_str!("tangling/test30/output");
+
+  let files = vec!["tests/tangling/test30/input".to_string()];
+  let files = input::open_files(files).unwrap();
+
+  let output_options = kaiseki::OutputOptions {
+    keep_source_line_gaps: true,
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_output(files, output_options).into();
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(INPUT.lines().count(), output.len());
+  for (line1, line2) in OUTPUT.lines().zip(output) {
+    assert_eq!(line1, &line2 as &str);
+  }
+}
+
+#[test]
+fn test_raw_region_separated() {
+  let input = "before\n// ##[raw]\nraw\n// ##[endraw]\nafter\n";
+  let output_options = kaiseki::OutputOptions {
+    block_separator: Some("---".to_string()),
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_str(input, output_options);
+
+  assert_eq!(errors.len(), 0);
+  assert_eq!(output, "before\n---\nraw\n---\nafter");
+}
+
+#[test]
+fn test_duplicate_drops_line_gaps() {
+  let input = "first\n// ##[label(Empty)]\nmiddle\n// ##[duplicate(Empty)]\nlast\n";
+  let output_options = kaiseki::OutputOptions {
+    keep_source_line_gaps: true,
+    ..Default::default()
+  };
+
+  let (output, errors) = kaiseki::tangle_str(input, output_options);
+
+  // Duplicated lines appear twice, so output lines can't match up with
+  // the input's, and no gaps are kept.
+  assert_eq!(errors.len(), 0);
+  assert_eq!(output, "first\nmiddle\nlast");
+}